@@ -2,6 +2,8 @@
 use std::borrow::Cow;
 
 use criterion::{criterion_group, criterion_main, Criterion};
+use fizzbuzz::game::Classic;
+use fizzbuzz::range::RangeFizzBuzz;
 use fizzbuzz::{self, FizzBuzz, FizzBuzzAnswer, MultiFizzBuzz};
 use rayon::prelude::*;
 
@@ -59,13 +61,57 @@ fn multifizzbuzz_trait_as_vec_cow() {
 #[inline]
 fn multifizzbuzz_trait_from_vec_as_answer() {
     let inputs: Vec<_> = (1..TEST_SIZE).collect();
-    let _: Vec<FizzBuzzAnswer> = inputs.fizzbuzz().collect();
+    let _: Vec<FizzBuzzAnswer<_>> = inputs.fizzbuzz().collect();
 }
 
 #[inline]
 fn multifizzbuzz_trait_from_range_as_answer() {
     let inputs = 1..TEST_SIZE;
-    let _: Vec<FizzBuzzAnswer> = inputs.fizzbuzz().collect();
+    let _: Vec<FizzBuzzAnswer<_>> = inputs.fizzbuzz().collect();
+}
+
+#[inline]
+fn multifizzbuzz_trait_from_range_as_answer_static_rules() {
+    let inputs = 1..TEST_SIZE;
+    let _: Vec<FizzBuzzAnswer<_>> = inputs.fizzbuzz_as::<Classic, _>().collect();
+}
+
+#[inline]
+fn range_wheel_as_answer() {
+    let inputs = 1..TEST_SIZE;
+    let _: Vec<FizzBuzzAnswer<_>> = inputs.fizzbuzz_wheel().collect();
+}
+
+#[inline]
+fn multifizzbuzz_write_to_sink() {
+    let inputs = 1..TEST_SIZE;
+    inputs.write_fizzbuzz(&mut std::io::sink(), "\n").unwrap();
+}
+
+#[inline]
+fn range_write_lines_to_sink() {
+    let inputs = 1..TEST_SIZE;
+    inputs.write_fizzbuzz_lines(&mut std::io::sink()).unwrap();
+}
+
+fn large_divisors() -> fizzbuzz::RuleSet<isize> {
+    fizzbuzz::RuleSet::new()
+        .divisor(1_009, "fizz")
+        .divisor(1_013, "buzz")
+        .divisor(1_019, "woof")
+        .divisor(1_021, "bang")
+}
+
+#[inline]
+fn multifizzbuzz_large_divisors_as_answer() {
+    let inputs = 1..TEST_SIZE;
+    let _: Vec<FizzBuzzAnswer<_>> = inputs.fizzbuzz_with(&large_divisors()).collect();
+}
+
+#[inline]
+fn range_sieve_large_divisors_as_answer() {
+    let inputs = 1..TEST_SIZE;
+    let _: Vec<FizzBuzzAnswer<_>> = inputs.fizzbuzz_sieve_with(&large_divisors()).collect();
 }
 
 fn criterion_benchmark(c: &mut Criterion) {
@@ -88,6 +134,23 @@ fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("multifizzbuzz_trait_from_range_as_answer", |b| {
         b.iter(multifizzbuzz_trait_from_range_as_answer)
     });
+    c.bench_function(
+        "multifizzbuzz_trait_from_range_as_answer_static_rules",
+        |b| b.iter(multifizzbuzz_trait_from_range_as_answer_static_rules),
+    );
+    c.bench_function("range_wheel_as_answer", |b| b.iter(range_wheel_as_answer));
+    c.bench_function("multifizzbuzz_write_to_sink", |b| {
+        b.iter(multifizzbuzz_write_to_sink)
+    });
+    c.bench_function("range_write_lines_to_sink", |b| {
+        b.iter(range_write_lines_to_sink)
+    });
+    c.bench_function("multifizzbuzz_large_divisors_as_answer", |b| {
+        b.iter(multifizzbuzz_large_divisors_as_answer)
+    });
+    c.bench_function("range_sieve_large_divisors_as_answer", |b| {
+        b.iter(range_sieve_large_divisors_as_answer)
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
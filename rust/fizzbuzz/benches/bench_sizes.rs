@@ -6,28 +6,28 @@ use rayon::iter::ParallelIterator;
 #[inline]
 fn range_20() {
     const TEST_SIZE: i32 = 20;
-    let _: Vec<FizzBuzzAnswer> = (1..TEST_SIZE).fizzbuzz().collect();
+    let _: Vec<FizzBuzzAnswer<_>> = (1..TEST_SIZE).fizzbuzz().collect();
 }
 
 #[inline]
 fn range_200_000() {
     const TEST_SIZE: i32 = 200_000;
-    let _: Vec<FizzBuzzAnswer> = (1..TEST_SIZE).fizzbuzz().collect();
+    let _: Vec<FizzBuzzAnswer<_>> = (1..TEST_SIZE).fizzbuzz().collect();
 }
 #[inline]
 fn range_300_000() {
     const TEST_SIZE: i32 = 300_000;
-    let _: Vec<FizzBuzzAnswer> = (1..TEST_SIZE).fizzbuzz().collect();
+    let _: Vec<FizzBuzzAnswer<_>> = (1..TEST_SIZE).fizzbuzz().collect();
 }
 #[inline]
 fn range_1_000_000() {
     const TEST_SIZE: i32 = 1_000_000;
-    let _: Vec<FizzBuzzAnswer> = (1..TEST_SIZE).fizzbuzz().collect();
+    let _: Vec<FizzBuzzAnswer<_>> = (1..TEST_SIZE).fizzbuzz().collect();
 }
 #[inline]
 fn range_10_000_000() {
     const TEST_SIZE: i32 = 10_000_000;
-    let _: Vec<FizzBuzzAnswer> = (1..TEST_SIZE).fizzbuzz().collect();
+    let _: Vec<FizzBuzzAnswer<_>> = (1..TEST_SIZE).fizzbuzz().collect();
 }
 
 fn criterion_benchmark(c: &mut Criterion) {
@@ -0,0 +1,227 @@
+//! A choice of how to spread work between threads, for deployments which cannot use rayon.
+//!
+//! The numbers are evaluated on a `Backend`:
+//! - `Backend::Rayon` uses rayon's global thread pool, or the pool of the current thread if
+//!   called within `ThreadPool::install()`. Only available with the `rayon` feature.
+//! - `Backend::Threads` splits the numbers into one contiguous chunk per thread and evaluates
+//!   the chunks with `std::thread::scope`, without using any rayon thread pool.
+//!
+//! Both return the answers in the same order as the input and evaluate inputs shorter than
+//! `parallel::threshold()` on the current thread. `Backend::default()` is `Rayon` if the feature
+//! is enabled, or else `Threads` with one thread per available CPU.
+//!
+//! With the `rayon` feature, the backend is passed to `MultiFizzBuzz::fizzbuzz_on()`,
+//! `fizzbuzz_with_on()` or `fizzbuzz_as_on()`, and `Backend::Threads` splits the indexed input
+//! directly, without copying it. Without the `rayon` feature, the same methods are provided
+//! by `BackendFizzBuzz` for any `IntoIterator`.
+//!
+//! ```
+//! use std::num::NonZeroUsize;
+//!
+//! use fizzbuzz::backend::Backend;
+//! # #[cfg(feature = "rayon")]
+//! use fizzbuzz::MultiFizzBuzz;
+//! # #[cfg(not(feature = "rayon"))]
+//! # use fizzbuzz::BackendFizzBuzz;
+//!
+//! let threads = Backend::Threads(NonZeroUsize::new(4).unwrap());
+//! let answers: Vec<String> = (1..1_000_000).fizzbuzz_on(threads);
+//! assert_eq!(answers[14], "fizzbuzz");
+//! ```
+
+use std::{num::NonZeroUsize, panic, thread};
+
+#[cfg(feature = "rayon")]
+use rayon::{
+    iter::plumbing::{Producer, ProducerCallback},
+    prelude::*,
+};
+
+use crate::parallel;
+#[cfg(not(feature = "rayon"))]
+use crate::{game::StaticRules, FizzBuzz, FizzBuzzAnswer, RuleSet};
+
+/// Where to evaluate answers in parallel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// rayon's global thread pool, or the pool of the current thread
+    #[cfg(feature = "rayon")]
+    Rayon,
+    /// A scope of this many `std::thread`s, each evaluating one contiguous chunk of the input
+    Threads(NonZeroUsize),
+}
+
+impl Backend {
+    /// `Backend::Threads` with one thread for each CPU available to this process.
+    pub fn threads() -> Self {
+        Backend::Threads(thread::available_parallelism().unwrap_or(NonZeroUsize::MIN))
+    }
+
+    /// Applies `f` to each of `inputs` and returns the outputs in order.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn map<Iter, Out, F>(self, inputs: Iter, f: F) -> Vec<Out>
+    where
+        Iter: IndexedParallelIterator,
+        Out: Send,
+        F: Fn(Iter::Item) -> Out + Send + Sync,
+    {
+        let len = inputs.len();
+        match self {
+            Backend::Rayon => inputs.with_min_len(crate::min_len(len)).map(f).collect(),
+            Backend::Threads(threads) => inputs.with_producer(OnThreads { threads, len, f }),
+        }
+    }
+
+    /// Applies `f` to each of `inputs` and returns the outputs in order.
+    #[cfg(not(feature = "rayon"))]
+    pub(crate) fn map<In, Out, F>(self, inputs: &[In], f: F) -> Vec<Out>
+    where
+        In: Sync,
+        Out: Send,
+        F: Fn(&In) -> Out + Sync,
+    {
+        let Backend::Threads(threads) = self;
+        // An empty input would make empty chunks, which `chunks` rejects
+        if inputs.is_empty() || inputs.len() < parallel::threshold() {
+            return inputs.iter().map(f).collect();
+        }
+        let chunk_len = inputs.len().div_ceil(threads.get());
+        on_threads(inputs.len(), inputs.chunks(chunk_len), |chunk| {
+            chunk.iter().map(&f).collect()
+        })
+    }
+}
+
+/// Evaluates each of `chunks` on its own scoped thread, and concatenates the outputs in order.
+fn on_threads<Chunk, Out, F>(len: usize, chunks: impl Iterator<Item = Chunk>, f: F) -> Vec<Out>
+where
+    Chunk: Send,
+    Out: Send,
+    F: Fn(Chunk) -> Vec<Out> + Sync,
+{
+    thread::scope(|scope| {
+        let chunks: Vec<_> = chunks.map(|chunk| scope.spawn(|| f(chunk))).collect();
+        let mut outputs = Vec::with_capacity(len);
+        for chunk in chunks {
+            match chunk.join() {
+                Ok(chunk) => outputs.extend(chunk),
+                Err(panic) => panic::resume_unwind(panic),
+            }
+        }
+        outputs
+    })
+}
+
+/// Splits the producer of an indexed input into one contiguous chunk per thread, so that the
+/// input is evaluated in place
+#[cfg(feature = "rayon")]
+struct OnThreads<F> {
+    threads: NonZeroUsize,
+    len: usize,
+    f: F,
+}
+
+#[cfg(feature = "rayon")]
+impl<In, Out, F> ProducerCallback<In> for OnThreads<F>
+where
+    Out: Send,
+    F: Fn(In) -> Out + Sync,
+{
+    type Output = Vec<Out>;
+
+    fn callback<P: Producer<Item = In>>(self, producer: P) -> Vec<Out> {
+        let OnThreads { threads, len, f } = self;
+        if len == 0 || len < parallel::threshold() {
+            return producer.into_iter().map(f).collect();
+        }
+        let chunk_len = len.div_ceil(threads.get());
+        let mut rest = Some((producer, len));
+        let chunks = std::iter::from_fn(|| {
+            let (producer, len) = rest.take()?;
+            if len > chunk_len {
+                let (chunk, tail) = producer.split_at(chunk_len);
+                rest = Some((tail, len - chunk_len));
+                Some(chunk)
+            } else {
+                Some(producer)
+            }
+        });
+        on_threads(len, chunks, |chunk| chunk.into_iter().map(&f).collect())
+    }
+}
+
+impl Default for Backend {
+    /// `Backend::Rayon` with the `rayon` feature, or else `Backend::threads()`
+    fn default() -> Self {
+        #[cfg(feature = "rayon")]
+        return Backend::Rayon;
+        #[cfg(not(feature = "rayon"))]
+        return Backend::threads();
+    }
+}
+
+/// Used to obtain the answers for multiple numbers in parallel on a chosen `Backend`, without the
+/// `rayon` feature.
+///
+/// The answers are evaluated eagerly and collected into a `Vec`. With the `rayon` feature,
+/// `MultiFizzBuzz` provides the same methods for indexed inputs.
+#[cfg(not(feature = "rayon"))]
+pub trait BackendFizzBuzz {
+    /// The type of the numbers being fizzbuzzed
+    type Num;
+
+    /// Returns the answers for the elements of the implementing type, in order, converted to a
+    /// requested type (e.g. `FizzBuzzAnswer`, `String`, `Cow<str>`).
+    ///
+    /// Note: this function **consumes** the input. A plain `Iterator` cannot be split between
+    /// threads, so the input is collected first, which reuses the allocation of a `Vec`.
+    fn fizzbuzz_on<Rtn>(self, backend: Backend) -> Vec<Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send;
+
+    /// As `fizzbuzz_on()`, when playing with a custom `RuleSet`.
+    fn fizzbuzz_with_on<Rtn>(self, rules: &RuleSet<Self::Num>, backend: Backend) -> Vec<Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send;
+
+    /// As `fizzbuzz_on()`, using a rule set which is fixed at compile time, such as
+    /// `game::Game<3, 7>`.
+    fn fizzbuzz_as_on<Rules, Rtn>(self, backend: Backend) -> Vec<Rtn>
+    where
+        Rules: StaticRules,
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send;
+}
+
+#[cfg(not(feature = "rayon"))]
+impl<Iterable, Num> BackendFizzBuzz for Iterable
+where
+    Iterable: IntoIterator<Item = Num>,
+    Num: FizzBuzz + Sync,
+{
+    type Num = Num;
+
+    fn fizzbuzz_on<Rtn>(self, backend: Backend) -> Vec<Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Num>> + Send,
+    {
+        let nums: Vec<Num> = self.into_iter().collect();
+        backend.map(&nums, |n| n.fizzbuzz().into())
+    }
+
+    fn fizzbuzz_with_on<Rtn>(self, rules: &RuleSet<Num>, backend: Backend) -> Vec<Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Num>> + Send,
+    {
+        let nums: Vec<Num> = self.into_iter().collect();
+        backend.map(&nums, |n| n.fizzbuzz_with(rules).into())
+    }
+
+    fn fizzbuzz_as_on<Rules, Rtn>(self, backend: Backend) -> Vec<Rtn>
+    where
+        Rules: StaticRules,
+        Rtn: From<FizzBuzzAnswer<Num>> + Send,
+    {
+        let nums: Vec<Num> = self.into_iter().collect();
+        backend.map(&nums, |n| n.fizzbuzz_as::<Rules>().into())
+    }
+}
@@ -0,0 +1,102 @@
+//! Parallel answers for borrowed collections, which are not consumed.
+
+use rayon::prelude::*;
+
+use crate::{game::StaticRules, FizzBuzz, FizzBuzzAnswer, RuleSet};
+
+/// Used to obtain the answers for the elements of a borrowed collection, such as a `&[Num]`,
+/// `&Vec<Num>` or `&VecDeque<Num>`, or any other rayon `IntoParallelIterator` over `&Num`.
+///
+/// This is the borrowing counterpart of `MultiFizzBuzz`, which consumes its input. The answers
+/// hold copies of the numbers, so they do not borrow from the collection.
+///
+/// There is no `impl FizzBuzz for &Num`: it would overlap with the blanket implementation of
+/// `FizzBuzz` for every `Num` which meets its bounds, as a reference could meet them too. Borrowed
+/// numbers are therefore answered via this trait, through each reference, or a sequential
+/// iterator over `&Num` can be `.copied()` before calling `SequentialFizzBuzz` methods.
+///
+/// ```
+/// use fizzbuzz::BorrowedFizzBuzz;
+/// use rayon::iter::ParallelIterator;
+///
+/// let numbers = vec![1, 2, 3, 4, 5];
+/// let fizzbuzzed: Vec<String> = (&numbers).fizzbuzz().collect();
+/// assert_eq!(fizzbuzzed, vec!["1", "2", "fizz", "4", "buzz"]);
+/// let fizzbuzzed: Vec<String> = numbers[2..].fizzbuzz().collect();
+/// assert_eq!(fizzbuzzed, vec!["fizz", "4", "buzz"]);
+/// assert_eq!(numbers.len(), 5);
+/// ```
+pub trait BorrowedFizzBuzz {
+    /// The type of the numbers being fizzbuzzed
+    type Num;
+
+    /// Returns an iterator which provides the FizzBuzz values for the elements of the borrowed
+    /// collection.
+    ///
+    /// Note:
+    /// - The returned iterator is a `rayon::iter::IndexedParallelIterator`
+    /// - The Items in the returned iterator will be converted to a requested type
+    ///   (e.g. `FizzBuzzAnswer`, `String`, `Cow<str>`)
+    fn fizzbuzz<Rtn>(self) -> impl IndexedParallelIterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send;
+
+    /// As `fizzbuzz()`, when playing with a custom `RuleSet`.
+    fn fizzbuzz_with<Rtn>(
+        self,
+        rules: &RuleSet<Self::Num>,
+    ) -> impl IndexedParallelIterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send;
+
+    /// As `fizzbuzz()`, using a rule set which is fixed at compile time, such as
+    /// `game::Game<3, 7>`.
+    fn fizzbuzz_as<Rules, Rtn>(self) -> impl IndexedParallelIterator<Item = Rtn>
+    where
+        Rules: StaticRules,
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send;
+}
+
+/// Implements the BorrowedFizzBuzz trait for any type which can be easily converted into a
+/// `rayon::iter::IndexedParallelIterator` over references to Items which implement
+/// `fizzbuzz::FizzBuzz`
+impl<'a, Iterable, Num> BorrowedFizzBuzz for Iterable
+where
+    Iterable: IntoParallelIterator<Item = &'a Num>,
+    <Iterable as IntoParallelIterator>::Iter: IndexedParallelIterator,
+    Num: FizzBuzz + Sync + 'a,
+{
+    type Num = Num;
+
+    fn fizzbuzz<Rtn>(self) -> impl IndexedParallelIterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Num>> + Send,
+    {
+        let par_iter = self.into_par_iter();
+        let min_len = crate::min_len(par_iter.len());
+        par_iter.with_min_len(min_len).map(|n| n.fizzbuzz().into())
+    }
+
+    fn fizzbuzz_with<Rtn>(self, rules: &RuleSet<Num>) -> impl IndexedParallelIterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Num>> + Send,
+    {
+        let par_iter = self.into_par_iter();
+        let min_len = crate::min_len(par_iter.len());
+        par_iter
+            .with_min_len(min_len)
+            .map(|n| n.fizzbuzz_with(rules).into())
+    }
+
+    fn fizzbuzz_as<Rules, Rtn>(self) -> impl IndexedParallelIterator<Item = Rtn>
+    where
+        Rules: StaticRules,
+        Rtn: From<FizzBuzzAnswer<Num>> + Send,
+    {
+        let par_iter = self.into_par_iter();
+        let min_len = crate::min_len(par_iter.len());
+        par_iter
+            .with_min_len(min_len)
+            .map(|n| n.fizzbuzz_as::<Rules>().into())
+    }
+}
@@ -0,0 +1,191 @@
+//! Fast divisibility tests for primitive integers.
+//!
+//! `n` is divisible by an odd divisor `d` if, and only if, `n * inverse(d) <= MAX / d`, using
+//! wrapping multiplication, where `inverse(d)` is the multiplicative inverse of `d` modulo
+//! `2^BITS`. For an even divisor `d = odd * 2^k`, the lowest `k` bits of the product must also be
+//! zero, which is checked in the same comparison by rotating them into the highest bits.
+//! (Granlund & Montgomery, "Division by invariant integers using multiplication", 1994)
+//!
+//! Calculating the inverse is more expensive than a single `%`, so it is only worthwhile for
+//! divisors which are used many times, such as those in a `RuleSet`. Divisibility by a literal
+//! constant, as in `FizzBuzz::fizzbuzz()`, is already optimised this way by the compiler.
+//!
+//! The primitive integers are identified through `rules::Divisor`, which they implement by
+//! providing their `Magnitude`.
+
+use crate::rules::Divisor;
+
+/// The constants needed to test for divisibility by a fixed divisor in the unsigned type `U`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Inverse<U> {
+    inverse: U,
+    shift: u32,
+    limit: U,
+}
+
+macro_rules! impl_inverse {
+    ($($u:ty),*) => {$(
+        impl Inverse<$u> {
+            /// `None` if `divisor` is zero.
+            fn new(divisor: $u) -> Option<Self> {
+                if divisor == 0 {
+                    return None;
+                }
+                let shift = divisor.trailing_zeros();
+                let odd = divisor >> shift;
+                // Newton's method: every odd number is its own inverse modulo 2^3 and each
+                // iteration doubles the number of correct bits, giving 192 correct bits.
+                let mut inverse = odd;
+                for _ in 0..6 {
+                    let error = (2 as $u).wrapping_sub(odd.wrapping_mul(inverse));
+                    inverse = inverse.wrapping_mul(error);
+                }
+                Some(Self {
+                    inverse,
+                    shift,
+                    limit: <$u>::MAX / divisor,
+                })
+            }
+
+            fn divides(&self, num: $u) -> bool {
+                num.wrapping_mul(self.inverse).rotate_right(self.shift) <= self.limit
+            }
+        }
+    )*};
+}
+
+impl_inverse!(u8, u16, u32, u64, u128);
+
+macro_rules! impl_fast_divisor {
+    ($($variant:ident($u:ty): $($t:ty),*;)*) => {
+        /// The magnitude of a primitive integer, in the unsigned type of the same width.
+        ///
+        /// Signed numbers and divisors are tested by their magnitudes, which are exact in the
+        /// unsigned type of the same width. `usize` and `isize` use 64 bits, which is exact on all
+        /// platforms.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum Magnitude {
+            $($variant($u),)*
+        }
+
+        /// A divisor of any primitive integer type, prepared for fast divisibility tests.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub(crate) enum FastDivisor {
+            $($variant(Inverse<$u>),)*
+        }
+
+        impl FastDivisor {
+            /// `None` if `divisor` is zero.
+            pub(crate) fn new(divisor: Magnitude) -> Option<Self> {
+                match divisor {
+                    $(Magnitude::$variant(divisor) => Inverse::<$u>::new(divisor).map(Self::$variant),)*
+                }
+            }
+
+            /// `true` if `num` is divisible by this divisor.
+            ///
+            /// # Panics
+            /// If `num` is not the width of the divisor.
+            pub(crate) fn divides(&self, num: Magnitude) -> bool {
+                match (self, num) {
+                    $((Self::$variant(inverse), Magnitude::$variant(num)) => inverse.divides(num),)*
+                    _ => unreachable!("divisor and number must be the same type"),
+                }
+            }
+        }
+
+        $($(
+            impl Divisor for $t {
+                #[inline]
+                fn magnitude(&self) -> Option<Magnitude> {
+                    Some(Magnitude::$variant(self.abs_diff(0) as $u))
+                }
+            }
+        )*)*
+    };
+}
+
+impl_fast_divisor! {
+    U8(u8): u8, i8;
+    U16(u16): u16, i16;
+    U32(u32): u32, i32;
+    U64(u64): u64, i64, usize, isize;
+    U128(u128): u128, i128;
+}
+
+impl Divisor for f32 {}
+impl Divisor for f64 {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Prepares `divisor` for fast divisibility tests
+    fn fast(divisor: impl Divisor) -> FastDivisor {
+        FastDivisor::new(divisor.magnitude().unwrap()).unwrap()
+    }
+
+    /// `true` if `fast` divides `num`
+    fn divides(fast: &FastDivisor, num: impl Divisor) -> bool {
+        fast.divides(num.magnitude().unwrap())
+    }
+
+    #[test]
+    fn every_u8_and_i8() {
+        for divisor in 1..=u8::MAX {
+            let fast = fast(divisor);
+            for num in 0..=u8::MAX {
+                assert_eq!(divides(&fast, num), num % divisor == 0, "{num} / {divisor}");
+            }
+        }
+        for divisor in (i8::MIN..=i8::MAX).filter(|&d| d != 0) {
+            let fast = fast(divisor);
+            for num in i8::MIN..=i8::MAX {
+                let expected = num.checked_rem(divisor).unwrap_or(0) == 0;
+                assert_eq!(divides(&fast, num), expected, "{num} / {divisor}");
+            }
+        }
+    }
+
+    #[test]
+    fn wide_types() {
+        let divisors = [
+            1_u128,
+            2,
+            3,
+            5,
+            6,
+            7,
+            15,
+            64,
+            96,
+            1_000_000_007,
+            u64::MAX as u128,
+        ];
+        let nums = (0..1_000_u128).chain((u64::MAX as u128 - 1_000)..=(u64::MAX as u128));
+        for num in nums {
+            for divisor in divisors {
+                let expected = num % divisor == 0;
+                assert_eq!(divides(&fast(divisor), num), expected, "{num} / {divisor}");
+                let fast64 = fast(divisor as u64);
+                assert_eq!(divides(&fast64, num as u64), expected, "{num} / {divisor}");
+                let signed = fast(-(divisor as i128));
+                assert_eq!(
+                    divides(&signed, -(num as i128)),
+                    expected,
+                    "{num} / {divisor}"
+                );
+            }
+        }
+        let max = fast(u128::MAX);
+        assert!(divides(&max, u128::MAX));
+        assert!(!divides(&max, u128::MAX - 1));
+        assert!(divides(&max, 0_u128));
+    }
+
+    #[test]
+    fn not_primitive() {
+        assert_eq!(3.0_f64.magnitude(), None);
+        assert_eq!(FastDivisor::new(Magnitude::U32(0)), None);
+    }
+}
@@ -0,0 +1,142 @@
+//! Rule sets which are fixed at compile time.
+//!
+//! For hot loops, the divisors and words can be provided as const generics or associated consts,
+//! allowing the compiler to fold the conversion of each divisor into the number type and the
+//! `%` by a constant divisor:
+//! - `Game<FIZZ, BUZZ>` plays fizzbuzz with any two divisors and the usual answers.
+//!   The classic game, as played by `FizzBuzz::fizzbuzz()`, is `Game<3, 5>`.
+//! - Any type implementing `StaticRules` can provide its own `const RULES`.
+//!
+//! ```
+//! use fizzbuzz::{FizzBuzz, FizzBuzzAnswer, SequentialFizzBuzz};
+//! use fizzbuzz::game::{Game, StaticRules};
+//!
+//! assert_eq!(14.fizzbuzz_as::<Game<2, 7>>(), FizzBuzzAnswer::Fizzbuzz);
+//!
+//! # #[cfg(feature = "alloc")] {
+//! struct Woof;
+//! impl StaticRules for Woof {
+//!     const RULES: &'static [(u8, &'static str)] = &[(3, "fizz"), (5, "buzz"), (7, "woof")];
+//! }
+//!
+//! let answers: Vec<String> = [7, 21, 22].into_iter().fizzbuzz_seq_as::<Woof, _>().collect();
+//! assert_eq!(answers, vec!["woof", "fizzwoof", "22"]);
+//! # }
+//! ```
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+use crate::FizzBuzzAnswer;
+
+/// A rule set which is fixed at compile time.
+pub trait StaticRules {
+    /// `(divisor, word)` pairs. When more than one divisor applies to a number the words are
+    /// concatenated in order.
+    const RULES: &'static [(u8, &'static str)];
+
+    /// Computes the answer for `num`.
+    ///
+    /// The default implementation returns the concatenated words for numbers which are divisible
+    /// by any of the divisors in `RULES` and a `FizzBuzzAnswer::Number` for all other numbers.
+    /// As for a `RuleSet`, the words "fizz", "buzz" and "fizzbuzz" are returned as
+    /// `FizzBuzzAnswer::Fizz`, `Buzz` and `Fizzbuzz`, and any others as a `FizzBuzzAnswer::Word`.
+    #[cfg(feature = "alloc")]
+    fn answer<Num>(num: &Num) -> FizzBuzzAnswer<Num>
+    where
+        Num: TryFrom<u8> + core::fmt::Display + PartialEq + Clone,
+        for<'a> &'a Num: core::ops::Rem<Num, Output = Num>,
+    {
+        const { assert!(no_zero_divisors(Self::RULES), "divisors cannot be zero") };
+        let zero = match <Num>::try_from(0_u8) {
+            Ok(zero) => zero,
+            Err(_) => return FizzBuzzAnswer::Number(num.clone()),
+        };
+        let mut words =
+            Self::RULES
+                .iter()
+                .filter_map(|&(divisor, word)| match <Num>::try_from(divisor) {
+                    Ok(divisor) => (num % divisor == zero).then_some(word),
+                    Err(_) => None,
+                });
+        match (words.next(), words.next()) {
+            (None, _) => FizzBuzzAnswer::Number(num.clone()),
+            (Some(word), None) => FizzBuzzAnswer::from_word(word.into()),
+            (Some(first), Some(second)) => {
+                let mut answer = String::from(first);
+                answer.push_str(second);
+                words.for_each(|word| answer.push_str(word));
+                FizzBuzzAnswer::from_word(answer.into())
+            }
+        }
+    }
+
+    /// Computes the answer for `num`.
+    ///
+    /// Without the `alloc` feature there is no default implementation, as the words for
+    /// divisors which apply together cannot be concatenated.
+    #[cfg(not(feature = "alloc"))]
+    fn answer<Num>(num: &Num) -> FizzBuzzAnswer<Num>
+    where
+        Num: TryFrom<u8> + core::fmt::Display + PartialEq + Clone,
+        for<'a> &'a Num: core::ops::Rem<Num, Output = Num>;
+}
+
+/// Checked at compile time for every `StaticRules` which is used
+#[cfg(feature = "alloc")]
+const fn no_zero_divisors(rules: &[(u8, &str)]) -> bool {
+    let mut i = 0;
+    while i < rules.len() {
+        if rules[i].0 == 0 {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Fizzbuzz, with the divisors `FIZZ` and `BUZZ` fixed at compile time.
+///
+/// Answers are `FizzBuzzAnswer::Fizz`, `Buzz`, `Fizzbuzz` or `Number`, exactly as for
+/// `FizzBuzz::fizzbuzz()`, which plays `Game<3, 5>`.
+///
+/// Zero divisors are rejected at compile time:
+/// ```compile_fail
+/// use fizzbuzz::{FizzBuzz, game::Game};
+/// 15.fizzbuzz_as::<Game<0, 5>>();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Game<const FIZZ: u8, const BUZZ: u8>;
+
+/// The classic game: `Game<3, 5>`
+pub type Classic = Game<3, 5>;
+
+impl<const FIZZ: u8, const BUZZ: u8> StaticRules for Game<FIZZ, BUZZ> {
+    const RULES: &'static [(u8, &'static str)] = &[(FIZZ, "fizz"), (BUZZ, "buzz")];
+
+    fn answer<Num>(num: &Num) -> FizzBuzzAnswer<Num>
+    where
+        Num: TryFrom<u8> + core::fmt::Display + PartialEq + Clone,
+        for<'a> &'a Num: core::ops::Rem<Num, Output = Num>,
+    {
+        const { assert!(FIZZ != 0 && BUZZ != 0, "divisors cannot be zero") };
+        let fizz = match <Num>::try_from(FIZZ) {
+            Ok(fizz) => fizz,
+            Err(_) => return FizzBuzzAnswer::Number(num.clone()),
+        };
+        let buzz = match <Num>::try_from(BUZZ) {
+            Ok(buzz) => buzz,
+            Err(_) => return FizzBuzzAnswer::Number(num.clone()),
+        };
+        let zero = match <Num>::try_from(0_u8) {
+            Ok(zero) => zero,
+            Err(_) => return FizzBuzzAnswer::Number(num.clone()),
+        };
+        match (num % fizz == zero, num % buzz == zero) {
+            (true, true) => FizzBuzzAnswer::Fizzbuzz,
+            (true, false) => FizzBuzzAnswer::Fizz,
+            (false, true) => FizzBuzzAnswer::Buzz,
+            _ => FizzBuzzAnswer::Number(num.clone()),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Integer arithmetic shared by ranges and sequences of primitive integers.
+
+use core::fmt::Display;
+
+/// Integer arithmetic needed to walk along a range or a `Sequence`.
+///
+/// Implemented for all primitive integer types, and sealed: the range and sequence code relies on
+/// the preconditions of its methods, so it cannot be implemented outside this crate.
+pub trait Integer: sealed::Sealed + Copy + Display + PartialOrd + Send + Sync + 'static {
+    /// The smallest value of the type
+    const MIN: Self;
+
+    /// The largest value of the type
+    const MAX: Self;
+
+    /// One, the default step
+    const ONE: Self;
+
+    /// The `i`th number after `self`, which must not overflow
+    fn nth(self, i: usize) -> Self;
+
+    /// The number `distance` after `self`, or before it if `descending`, which must not overflow
+    fn offset(self, distance: u128, descending: bool) -> Self;
+
+    /// The count of steps of one from `start` to `self`
+    fn span(self, start: Self) -> u128;
+
+    /// The count of numbers from `start` up to `self`, if it fits into a `usize`
+    fn distance(self, start: Self) -> Option<usize>;
+
+    /// The absolute value
+    fn magnitude(self) -> u128;
+
+    /// `true` if greater than zero
+    fn is_positive(self) -> bool;
+
+    /// The count of numbers from `self` up to the next multiple of the non-zero `divisor`
+    fn to_multiple(self, divisor: Self) -> u128;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {$(
+        impl sealed::Sealed for $t {}
+
+        impl Integer for $t {
+            const MIN: Self = <$t>::MIN;
+            const MAX: Self = <$t>::MAX;
+            const ONE: Self = 1;
+
+            fn nth(self, i: usize) -> Self {
+                self.wrapping_add(i as $t)
+            }
+
+            fn offset(self, distance: u128, descending: bool) -> Self {
+                if descending {
+                    self.wrapping_sub(distance as $t)
+                } else {
+                    self.wrapping_add(distance as $t)
+                }
+            }
+
+            fn span(self, start: Self) -> u128 {
+                self.abs_diff(start) as u128
+            }
+
+            fn distance(self, start: Self) -> Option<usize> {
+                usize::try_from(self.abs_diff(start)).ok()
+            }
+
+            fn magnitude(self) -> u128 {
+                self.abs_diff(0) as u128
+            }
+
+            fn is_positive(self) -> bool {
+                self > 0
+            }
+
+            fn to_multiple(self, divisor: Self) -> u128 {
+                // Only `MIN % -1` overflows, and `MIN` is a multiple of `-1`
+                match self.checked_rem_euclid(divisor).unwrap_or(0) {
+                    0 => 0,
+                    remainder => divisor.magnitude() - remainder.magnitude(),
+                }
+            }
+        }
+    )*};
+}
+
+impl_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
@@ -5,6 +5,7 @@
 //! ## Example usage for single item:
 //!
 //! ```
+//! # #[cfg(feature = "alloc")] {
 //! use fizzbuzz::FizzBuzz;
 //! use std::borrow::Cow;
 //!
@@ -12,51 +13,211 @@
 //! let three: String = 3.fizzbuzz().into();
 //! assert_eq!(&one, "1");
 //! assert_eq!(three, "fizz");
+//! # }
 //! ```
 //!
 //! ## Example usage for multiple items:
 //!
 //! ```
+//! # #[cfg(feature = "rayon")] {
 //! use fizzbuzz::MultiFizzBuzz;
 //! use rayon::iter::ParallelIterator; // required to `.collect()` the results
 //!
 //! let one_to_five = vec![1,2,3,4,5];
 //! let fizzbuzzed: Vec<String> = one_to_five.fizzbuzz().collect();
 //! assert_eq!(fizzbuzzed, vec!["1", "2", "fizz", "4", "buzz"]);
+//! # }
 //! ```
+//!
+//! ## Example usage for multiple items, sequentially:
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! use fizzbuzz::SequentialFizzBuzz;
+//!
+//! let fizzbuzzed: Vec<String> = [1, 2, 3, 4, 5].into_iter().fizzbuzz_seq().collect();
+//! assert_eq!(fizzbuzzed, vec!["1", "2", "fizz", "4", "buzz"]);
+//! # }
+//! ```
+//!
+//! ## Example usage with custom rules:
+//!
+//! ```
+//! # #[cfg(feature = "rayon")] {
+//! use fizzbuzz::{MultiFizzBuzz, RuleSet};
+//! use rayon::iter::ParallelIterator;
+//!
+//! let rules = RuleSet::default().divisor(7, "woof");
+//! let fizzbuzzed: Vec<String> = vec![5, 7, 35, 105].fizzbuzz_with(&rules).collect();
+//! assert_eq!(fizzbuzzed, vec!["buzz", "woof", "buzzwoof", "fizzbuzzwoof"]);
+//! # }
+//! ```
+//!
+//! ## Example usage writing to a sink:
+//!
+//! ```
+//! # #[cfg(feature = "rayon")] {
+//! use fizzbuzz::MultiFizzBuzz;
+//!
+//! let mut out = Vec::new(); // or a `File`, `TcpStream`, `Stdout` ...
+//! (1..6).write_fizzbuzz(&mut out, "\n").unwrap();
+//! assert_eq!(out, b"1\n2\nfizz\n4\nbuzz");
+//! # }
+//! ```
+//!
+//! ## Example usage without `std` or an allocator:
+//!
+//! ```
+//! use fizzbuzz::FizzBuzz;
+//!
+//! let mut buf = [0; 8];
+//! assert_eq!(15.write_fizzbuzz_buf(&mut buf), Ok("fizzbuzz"));
+//! assert_eq!(1234.write_fizzbuzz_buf(&mut buf), Ok("1234"));
+//! ```
+//!
+//! ## Features
+//!
+//! The core of the crate is `no_std`: `FizzBuzz`, `SequentialFizzBuzz`, `game`, and the stateful
+//! rules, whose answers can be written into a `core::fmt::Write` sink or a fixed-size buffer.
+//!
+//! - `std` (default): `io::Write` sinks, and `backend::Backend::Threads` to spread work between
+//!   `std::thread`s with the threshold from the `parallel` module, via `BackendFizzBuzz` if
+//!   `rayon` is not enabled. Implies `alloc`.
+//! - `alloc`: conversion of answers into `String` and `Cow<str>`, custom `RuleSet`s and the
+//!   `parse` module. Without it, a `Word` can only be created from a `&'static str`.
+//! - `rayon` (default): parallel evaluation with `MultiFizzBuzz`, `BorrowedFizzBuzz`,
+//!   `UnindexedFizzBuzz`, `range::RangeFizzBuzz`, `backend::Backend::Rayon` and the thread pools
+//!   in the `parallel` module. `MultiFizzBuzz` can also evaluate its input on any
+//!   `backend::Backend`. Without it, `SequentialFizzBuzz` evaluates any `Iterator` lazily on
+//!   the current thread. Implies `std`.
+//! - `toml` / `json`: parse a `RuleSet` from TOML or JSON documents. Imply `std`.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
-use std::borrow::Cow;
+#[cfg(feature = "alloc")]
+use alloc::{
+    borrow::Cow,
+    string::{String, ToString},
+};
+use core::fmt::{self, Display};
+#[cfg(feature = "std")]
+use std::io;
 
-use rayon::prelude::*;
-static BIG_VECTOR: usize = 300_000; // Size from which parallelisation makes sense
+#[cfg(feature = "rayon")]
+use rayon::{prelude::*, ThreadPool};
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg(feature = "std")]
+pub mod backend;
+#[cfg(feature = "rayon")]
+mod borrowed;
+#[cfg(feature = "alloc")]
+mod fastdiv;
+pub mod game;
+mod integer;
+#[cfg(feature = "std")]
+pub mod parallel;
+#[cfg(feature = "alloc")]
+pub mod parse;
+#[cfg(feature = "rayon")]
+mod primitive;
+#[cfg(feature = "rayon")]
+pub mod range;
+#[cfg(feature = "alloc")]
+pub mod rules;
+pub mod sequence;
+mod sequential;
+mod sink;
+pub mod stateful;
+#[cfg(feature = "rayon")]
+mod unindexed;
+mod word;
+#[cfg(feature = "rayon")]
+use backend::Backend;
+#[cfg(all(feature = "std", not(feature = "rayon")))]
+pub use backend::BackendFizzBuzz;
+#[cfg(feature = "rayon")]
+pub use borrowed::BorrowedFizzBuzz;
+use game::{Classic, StaticRules};
+#[cfg(feature = "alloc")]
+pub use rules::RuleSet;
+pub use sequence::Sequence;
+pub use sequential::SequentialFizzBuzz;
+pub use stateful::StatefulRule;
+#[cfg(feature = "rayon")]
+pub use unindexed::UnindexedFizzBuzz;
+pub use word::Word;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 /// Represents a valid answer to fizzbuzz and provides conversion to `String` and `Cow<&str>` via `.into()`
-pub enum FizzBuzzAnswer {
+///
+/// A `Number` holds the original number, which is only formatted when the answer is displayed or
+/// converted, so creating an answer never allocates.
+pub enum FizzBuzzAnswer<Num> {
     Fizz,
     Buzz,
     Fizzbuzz,
-    Number(String),
+    Number(Num),
+    /// The word(s) said in place of the number when playing with a custom `RuleSet`
+    Word(Word),
 }
 
-impl From<FizzBuzzAnswer> for Cow<'static, str> {
-    fn from(answer: FizzBuzzAnswer) -> Self {
+impl<Num> FizzBuzzAnswer<Num> {
+    /// The word said in place of the number, or `None` for a `FizzBuzzAnswer::Number`
+    pub fn word(&self) -> Option<&str> {
+        match self {
+            FizzBuzzAnswer::Fizz => Some("fizz"),
+            FizzBuzzAnswer::Buzz => Some("buzz"),
+            FizzBuzzAnswer::Fizzbuzz => Some("fizzbuzz"),
+            FizzBuzzAnswer::Number(_) => None,
+            FizzBuzzAnswer::Word(w) => Some(w),
+        }
+    }
+
+    /// The classic answer for "fizz", "buzz" or "fizzbuzz", otherwise a `FizzBuzzAnswer::Word`
+    pub(crate) fn from_word(word: Word) -> Self {
+        match &*word {
+            "fizz" => FizzBuzzAnswer::Fizz,
+            "buzz" => FizzBuzzAnswer::Buzz,
+            "fizzbuzz" => FizzBuzzAnswer::Fizzbuzz,
+            _ => FizzBuzzAnswer::Word(word),
+        }
+    }
+}
+
+impl<Num: Display> Display for FizzBuzzAnswer<Num> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FizzBuzzAnswer::Number(n) => n.fmt(f),
+            answer => f.pad(answer.word().unwrap_or_default()),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<Num: Display> From<FizzBuzzAnswer<Num>> for Cow<'static, str> {
+    fn from(answer: FizzBuzzAnswer<Num>) -> Self {
         match answer {
             FizzBuzzAnswer::Fizz => "fizz".into(),
             FizzBuzzAnswer::Buzz => "buzz".into(),
             FizzBuzzAnswer::Fizzbuzz => "fizzbuzz".into(),
-            FizzBuzzAnswer::Number(n) => n.into(),
+            FizzBuzzAnswer::Number(n) => n.to_string().into(),
+            FizzBuzzAnswer::Word(w) => w.into(),
         }
     }
 }
 
-impl From<FizzBuzzAnswer> for String {
-    fn from(answer: FizzBuzzAnswer) -> Self {
+#[cfg(feature = "alloc")]
+impl<Num: Display> From<FizzBuzzAnswer<Num>> for String {
+    fn from(answer: FizzBuzzAnswer<Num>) -> Self {
         match answer {
             FizzBuzzAnswer::Fizz => "fizz".into(),
             FizzBuzzAnswer::Buzz => "buzz".into(),
             FizzBuzzAnswer::Fizzbuzz => "fizzbuzz".into(),
-            FizzBuzzAnswer::Number(n) => n,
+            FizzBuzzAnswer::Number(n) => n.to_string(),
+            FizzBuzzAnswer::Word(w) => w.into(),
         }
     }
 }
@@ -77,53 +238,236 @@ pub trait FizzBuzz {
     ///
     /// A default implementation is available for any type `<Num>` which supports
     /// - `<Num>::try_from(<u8>)`: `Num` must be able to be constructed from `0`, `3` & `5`.
-    /// - `std::fmt::Display`: Allows `Num` to be formatted as a `String`.
+    /// - `core::fmt::Display`: Allows `Num` to be formatted as a `String`.
     /// - `PartialEq`: Enables comparison operations for `Num`.
     /// - `<&Num>::Rem<Num, Output = Num>`: Allows `&Num % Num`.
-    fn fizzbuzz(&self) -> FizzBuzzAnswer;
+    ///
+    /// For primitive integers the conversions are resolved at compile time, so cost nothing.
+    fn fizzbuzz(&self) -> FizzBuzzAnswer<Self>
+    where
+        Self: Sized;
+
+    /// Computes the answer for the implementing type when playing with a custom `RuleSet`.
+    ///
+    /// Returns a `FizzBuzzAnswer::Word` containing the words for the rules which apply,
+    /// combined as defined by the `RuleSet`, or the `RuleSet`'s fallback if no rules apply.
+    #[cfg(feature = "alloc")]
+    fn fizzbuzz_with(&self, rules: &RuleSet<Self>) -> FizzBuzzAnswer<Self>
+    where
+        Self: Sized;
+
+    /// Computes the answer for the implementing type using a rule set which is fixed at compile
+    /// time, such as `game::Game<3, 7>`.
+    fn fizzbuzz_as<Rules: StaticRules>(&self) -> FizzBuzzAnswer<Self>
+    where
+        Self: Sized;
+
+    /// Writes the answer for the implementing type into an `io::Write` sink, such as a file or
+    /// socket, without allocating a `String`.
+    #[cfg(feature = "std")]
+    fn write_fizzbuzz(&self, out: &mut impl io::Write) -> io::Result<()>
+    where
+        Self: Sized;
+
+    /// Writes the answer for the implementing type into a `fmt::Write` sink, such as a `String`
+    /// or a `fmt::Formatter`.
+    fn write_fizzbuzz_fmt(&self, out: &mut impl fmt::Write) -> fmt::Result
+    where
+        Self: Sized;
+
+    /// Writes the answer for the implementing type into the start of `buf` and returns the text
+    /// which was written, or an error if `buf` is too short.
+    ///
+    /// Needs neither `std` nor an allocator. 8 bytes are enough for any word in the classic game,
+    /// and 40 bytes for any primitive integer.
+    fn write_fizzbuzz_buf<'buf>(&self, buf: &'buf mut [u8]) -> Result<&'buf str, fmt::Error>
+    where
+        Self: Sized;
 }
 
 /// Implements the FizzBuzz trait for any type `<T>` which supports `<T>::from(<u8>)`
 /// and `<T> % <T>`
 impl<Num> FizzBuzz for Num
 where
-    Num: TryFrom<u8> + std::fmt::Display + PartialEq,
-    for<'a> &'a Num: std::ops::Rem<Num, Output = Num>,
+    Num: TryFrom<u8> + Display + PartialEq + Clone,
+    for<'a> &'a Num: core::ops::Rem<Num, Output = Num>,
 {
-    fn fizzbuzz(&self) -> FizzBuzzAnswer {
-        let three = match <Num>::try_from(3_u8) {
-            Ok(three) => three,
-            Err(_) => return FizzBuzzAnswer::Number(self.to_string()),
-        };
-        let five = match <Num>::try_from(5_u8) {
-            Ok(five) => five,
-            Err(_) => return FizzBuzzAnswer::Number(self.to_string()),
-        };
-        let zero = match <Num>::try_from(0_u8) {
-            Ok(zero) => zero,
-            Err(_) => return FizzBuzzAnswer::Number(self.to_string()),
-        };
-        match (self % three == zero, self % five == zero) {
-            (true, true) => FizzBuzzAnswer::Fizzbuzz,
-            (true, false) => FizzBuzzAnswer::Fizz,
-            (false, true) => FizzBuzzAnswer::Buzz,
-            _ => FizzBuzzAnswer::Number(self.to_string()),
-        }
+    fn fizzbuzz(&self) -> FizzBuzzAnswer<Num> {
+        Classic::answer(self)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn fizzbuzz_with(&self, rules: &RuleSet<Self>) -> FizzBuzzAnswer<Num> {
+        rules.answer(self)
+    }
+
+    fn fizzbuzz_as<Rules: StaticRules>(&self) -> FizzBuzzAnswer<Num> {
+        Rules::answer(self)
+    }
+
+    #[cfg(feature = "std")]
+    fn write_fizzbuzz(&self, out: &mut impl io::Write) -> io::Result<()> {
+        sink::write_answer_io(&self.fizzbuzz(), out)
+    }
+
+    fn write_fizzbuzz_fmt(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        sink::write_answer(&self.fizzbuzz(), out)
+    }
+
+    fn write_fizzbuzz_buf<'buf>(&self, buf: &'buf mut [u8]) -> Result<&'buf str, fmt::Error> {
+        sink::write_answer_buf(&self.fizzbuzz(), buf)
+    }
+}
+
+/// The minimum length of each parallel job for an input of `len` elements
+#[cfg(feature = "rayon")]
+fn min_len(len: usize) -> usize {
+    let threshold = parallel::threshold();
+    if len < threshold {
+        threshold //Don't parallelise when small
+    } else {
+        1
     }
 }
 
 /// Used to obtain the correct `FizzBuzzAnswer` for a multiple fizzbuzz-able numbers
+#[cfg(feature = "rayon")]
 pub trait MultiFizzBuzz {
+    /// The type of the numbers being fizzbuzzed
+    type Num;
+
     /// Returns an iterator which provides the FizzBuzz values for the elements of the implementing type.
     ///
     /// Note:
-    /// - This function **consumes** the input
+    /// - This function **consumes** the input: `BorrowedFizzBuzz` evaluates borrowed collections
     /// - The returned iterator is a `rayon::iter::IndexedParallelIterator`
     /// - The Items in the returned iterator will be converted to a requested type
     ///   (e.g. `FizzBuzzAnswer`, `String`, `Cow<str>`)
     fn fizzbuzz<Rtn>(self) -> impl IndexedParallelIterator<Item = Rtn>
     where
-        Rtn: From<FizzBuzzAnswer> + Send;
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send;
+
+    /// Returns an iterator which provides the answers for the elements of the implementing type
+    /// when playing with a custom `RuleSet`.
+    ///
+    /// Note: as for `fizzbuzz()`, this function **consumes** the input.
+    fn fizzbuzz_with<Rtn>(
+        self,
+        rules: &RuleSet<Self::Num>,
+    ) -> impl IndexedParallelIterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send;
+
+    /// Returns an iterator which provides the answers for the elements of the implementing type
+    /// using a rule set which is fixed at compile time, such as `game::Game<3, 7>`.
+    ///
+    /// Note: as for `fizzbuzz()`, this function **consumes** the input.
+    fn fizzbuzz_as<Rules, Rtn>(self) -> impl IndexedParallelIterator<Item = Rtn>
+    where
+        Rules: StaticRules,
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send;
+
+    /// Returns an iterator which provides the answers for the elements of the implementing type
+    /// after applying a `StatefulRule`, whose answers depend on the answers for earlier elements.
+    ///
+    /// Note:
+    /// - This function **consumes** the input
+    /// - The stateless answers and the state for each element are calculated _eagerly_, using a
+    ///   parallel prefix scan; only the conversion into `Rtn` is lazy.
+    fn fizzbuzz_scan<Rtn, Rule>(self, rule: &Rule) -> impl IndexedParallelIterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send,
+        Rule: StatefulRule<Self::Num>,
+        Self::Num: Send + Sync;
+
+    /// As `fizzbuzz_scan()` but using a custom `RuleSet` to provide the stateless answers.
+    fn fizzbuzz_with_scan<Rtn, Rule>(
+        self,
+        rules: &RuleSet<Self::Num>,
+        rule: &Rule,
+    ) -> impl IndexedParallelIterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send,
+        Rule: StatefulRule<Self::Num>,
+        Self::Num: Send + Sync;
+
+    /// Writes the answers for the elements of the implementing type into an `io::Write` sink,
+    /// such as a file or socket, with `separator` between each answer.
+    ///
+    /// Note:
+    /// - This function **consumes** the input
+    /// - The answers are calculated in parallel and streamed through a set of reusable buffers,
+    ///   so memory use does not grow with the number of answers
+    /// - Nothing is written after the final answer, and the sink is not flushed
+    fn write_fizzbuzz(self, out: &mut impl io::Write, separator: &str) -> io::Result<()>
+    where
+        Self::Num: Display;
+
+    /// As `write_fizzbuzz()` but for a `fmt::Write` sink, such as a `String`.
+    fn write_fizzbuzz_fmt(self, out: &mut impl fmt::Write, separator: &str) -> fmt::Result
+    where
+        Self::Num: Display;
+
+    /// As `fizzbuzz()`, but writes the answers into the caller's `out`, replacing its contents.
+    ///
+    /// Reusing the same `out` for each batch avoids allocating new storage for the answers.
+    ///
+    /// # Panics
+    /// If `out` is not the same length as the input.
+    fn fizzbuzz_into<Rtn>(self, out: &mut [Rtn])
+    where
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send;
+
+    /// As `fizzbuzz()`, but collects the answers into the caller's `out` with rayon's
+    /// `collect_into_vec()`, replacing its contents.
+    ///
+    /// `out` only reallocates if its capacity is smaller than the input, so reusing the same `out`
+    /// for each batch avoids the allocation which `.collect()` performs every time.
+    fn fizzbuzz_into_vec<Rtn>(self, out: &mut Vec<Rtn>)
+    where
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send;
+
+    /// As `fizzbuzz()`, but evaluates the answers on `pool` rather than the global rayon thread
+    /// pool and collects them into any collection which rayon can collect into (e.g. `Vec<Rtn>`).
+    ///
+    /// The lazy iterators returned by other methods can be confined to a pool by consuming them
+    /// within `pool.install()`.
+    fn fizzbuzz_in<Rtn, Collection>(self, pool: &ThreadPool) -> Collection
+    where
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send,
+        Collection: FromParallelIterator<Rtn> + Send,
+        Self: Send;
+
+    /// As `write_fizzbuzz()`, but renders the answers on `pool` rather than the global rayon
+    /// thread pool. Writing to `out` happens on the current thread.
+    fn write_fizzbuzz_in(
+        self,
+        out: &mut impl io::Write,
+        separator: &str,
+        pool: &ThreadPool,
+    ) -> io::Result<()>
+    where
+        Self::Num: Display;
+
+    /// As `fizzbuzz()`, but evaluates the answers on `backend` and collects them into a `Vec`.
+    ///
+    /// `Backend::Threads` splits the input into one contiguous chunk per thread, without copying
+    /// it, and evaluates the chunks with `std::thread::scope` rather than a rayon thread pool.
+    fn fizzbuzz_on<Rtn>(self, backend: Backend) -> Vec<Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send;
+
+    /// As `fizzbuzz_on()`, when playing with a custom `RuleSet`.
+    fn fizzbuzz_with_on<Rtn>(self, rules: &RuleSet<Self::Num>, backend: Backend) -> Vec<Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send;
+
+    /// As `fizzbuzz_on()`, using a rule set which is fixed at compile time, such as
+    /// `game::Game<3, 7>`.
+    fn fizzbuzz_as_on<Rules, Rtn>(self, backend: Backend) -> Vec<Rtn>
+    where
+        Rules: StaticRules,
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send;
 }
 
 /// Implements the MultiFizzBuzz trait for any type which can be easily converted into a
@@ -133,36 +477,190 @@ pub trait MultiFizzBuzz {
 /// - The returned iterator is _lazy_ - no calculations are performed until you use it
 /// - Collecting this iterator requires that `rayon::iter::ParallelIterator` is in scope
 /// - This implementation will decide whether it is worth the overhead of spawning multiple parallel threads
+/// - Inputs which are not indexed, such as the output of `filter()`, can be evaluated with `UnindexedFizzBuzz`
+#[cfg(feature = "rayon")]
 impl<Iterable, Num> MultiFizzBuzz for Iterable
 where
     Iterable: rayon::iter::IntoParallelIterator<Item = Num>,
     <Iterable as IntoParallelIterator>::Iter: IndexedParallelIterator,
     Num: FizzBuzz,
 {
+    type Num = Num;
+
     fn fizzbuzz<Rtn>(self) -> impl IndexedParallelIterator<Item = Rtn>
     where
-        Rtn: From<FizzBuzzAnswer> + Send,
+        Rtn: From<FizzBuzzAnswer<Num>> + Send,
     {
         let par_iter = self.into_par_iter();
-        let min_len = if par_iter.len() < BIG_VECTOR {
-            BIG_VECTOR //Don't parallelise when small
-        } else {
-            1
-        };
+        let min_len = min_len(par_iter.len());
         par_iter.with_min_len(min_len).map(|n| n.fizzbuzz().into())
     }
+
+    fn fizzbuzz_with<Rtn>(self, rules: &RuleSet<Num>) -> impl IndexedParallelIterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Num>> + Send,
+    {
+        let par_iter = self.into_par_iter();
+        let min_len = min_len(par_iter.len());
+        par_iter
+            .with_min_len(min_len)
+            .map(|n| n.fizzbuzz_with(rules).into())
+    }
+
+    fn fizzbuzz_as<Rules, Rtn>(self) -> impl IndexedParallelIterator<Item = Rtn>
+    where
+        Rules: StaticRules,
+        Rtn: From<FizzBuzzAnswer<Num>> + Send,
+    {
+        let par_iter = self.into_par_iter();
+        let min_len = min_len(par_iter.len());
+        par_iter
+            .with_min_len(min_len)
+            .map(|n| n.fizzbuzz_as::<Rules>().into())
+    }
+
+    fn fizzbuzz_scan<Rtn, Rule>(self, rule: &Rule) -> impl IndexedParallelIterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Num>> + Send,
+        Rule: StatefulRule<Num>,
+        Num: Send + Sync,
+    {
+        let par_iter = self.into_par_iter();
+        let min_len = min_len(par_iter.len());
+        let answers = par_iter.with_min_len(min_len).map(|n| {
+            let answer = n.fizzbuzz();
+            (n, answer)
+        });
+        stateful::scan(answers, rule)
+    }
+
+    fn fizzbuzz_with_scan<Rtn, Rule>(
+        self,
+        rules: &RuleSet<Num>,
+        rule: &Rule,
+    ) -> impl IndexedParallelIterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Num>> + Send,
+        Rule: StatefulRule<Num>,
+        Num: Send + Sync,
+    {
+        let par_iter = self.into_par_iter();
+        let min_len = min_len(par_iter.len());
+        let answers = par_iter.with_min_len(min_len).map(|n| {
+            let answer = n.fizzbuzz_with(rules);
+            (n, answer)
+        });
+        stateful::scan(answers, rule)
+    }
+
+    fn write_fizzbuzz(self, out: &mut impl io::Write, separator: &str) -> io::Result<()>
+    where
+        Num: Display,
+    {
+        sink::write_all(
+            self.into_par_iter(),
+            None,
+            separator,
+            Num::fizzbuzz,
+            |text| out.write_all(text.as_bytes()),
+        )
+    }
+
+    fn write_fizzbuzz_fmt(self, out: &mut impl fmt::Write, separator: &str) -> fmt::Result
+    where
+        Num: Display,
+    {
+        sink::write_all(
+            self.into_par_iter(),
+            None,
+            separator,
+            Num::fizzbuzz,
+            |text| out.write_str(text),
+        )
+    }
+
+    fn fizzbuzz_into<Rtn>(self, out: &mut [Rtn])
+    where
+        Rtn: From<FizzBuzzAnswer<Num>> + Send,
+    {
+        let answers = self.fizzbuzz();
+        assert_eq!(
+            out.len(),
+            answers.len(),
+            "output must be the same length as the input"
+        );
+        out.par_iter_mut()
+            .zip(answers)
+            .for_each(|(slot, answer)| *slot = answer);
+    }
+
+    fn fizzbuzz_into_vec<Rtn>(self, out: &mut Vec<Rtn>)
+    where
+        Rtn: From<FizzBuzzAnswer<Num>> + Send,
+    {
+        self.fizzbuzz().collect_into_vec(out);
+    }
+
+    fn fizzbuzz_in<Rtn, Collection>(self, pool: &ThreadPool) -> Collection
+    where
+        Rtn: From<FizzBuzzAnswer<Num>> + Send,
+        Collection: FromParallelIterator<Rtn> + Send,
+        Self: Send,
+    {
+        let threshold = parallel::threshold();
+        pool.install(|| parallel::with_threshold(threshold, || self.fizzbuzz().collect()))
+    }
+
+    fn write_fizzbuzz_in(
+        self,
+        out: &mut impl io::Write,
+        separator: &str,
+        pool: &ThreadPool,
+    ) -> io::Result<()>
+    where
+        Num: Display,
+    {
+        let par_iter = self.into_par_iter();
+        sink::write_all(par_iter, Some(pool), separator, Num::fizzbuzz, |text| {
+            out.write_all(text.as_bytes())
+        })
+    }
+
+    fn fizzbuzz_on<Rtn>(self, backend: Backend) -> Vec<Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Num>> + Send,
+    {
+        backend.map(self.into_par_iter(), |n| n.fizzbuzz().into())
+    }
+
+    fn fizzbuzz_with_on<Rtn>(self, rules: &RuleSet<Num>, backend: Backend) -> Vec<Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Num>> + Send,
+    {
+        backend.map(self.into_par_iter(), |n| n.fizzbuzz_with(rules).into())
+    }
+
+    fn fizzbuzz_as_on<Rules, Rtn>(self, backend: Backend) -> Vec<Rtn>
+    where
+        Rules: StaticRules,
+        Rtn: From<FizzBuzzAnswer<Num>> + Send,
+    {
+        backend.map(self.into_par_iter(), |n| n.fizzbuzz_as::<Rules>().into())
+    }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "rayon"))]
 mod test {
 
-    use super::*;
+    use rayon::iter::ParallelIterator;
+
+    use super::{parallel, FizzBuzz, FizzBuzzAnswer, MultiFizzBuzz};
 
     #[test]
     fn big_vector_is_well_ordered() {
-        let input: Vec<_> = (1..BIG_VECTOR + 2).collect();
-        let output: Vec<FizzBuzzAnswer> = input.clone().fizzbuzz().collect();
-        let mut expected: Vec<FizzBuzzAnswer> = vec![];
+        let input: Vec<_> = (1..parallel::DEFAULT_THRESHOLD + 2).collect();
+        let output: Vec<FizzBuzzAnswer<usize>> = input.clone().fizzbuzz().collect();
+        let mut expected: Vec<FizzBuzzAnswer<usize>> = vec![];
         for i in input.iter() {
             expected.push(i.fizzbuzz())
         }
@@ -172,11 +670,11 @@ mod test {
     #[test]
     fn fizzbuzz_range() {
         let input = 1..20;
-        let mut expected: Vec<FizzBuzzAnswer> = vec![];
+        let mut expected: Vec<FizzBuzzAnswer<i32>> = vec![];
         for i in 1..20 {
             expected.push(i.fizzbuzz())
         }
-        let output: Vec<FizzBuzzAnswer> = input.fizzbuzz().collect();
+        let output: Vec<FizzBuzzAnswer<i32>> = input.fizzbuzz().collect();
         assert_eq!(output, expected)
     }
 }
@@ -0,0 +1,162 @@
+//! Control of when `MultiFizzBuzz`, `RangeFizzBuzz` and `BackendFizzBuzz` use more than one
+//! thread.
+//!
+//! Inputs with fewer elements than the _threshold_ are evaluated in a single job, as the
+//! overhead of splitting them between threads outweighs the benefit. The break-even point depends
+//! on the machine and the type of answer being produced, so the threshold can be:
+//! - set globally with `set_threshold()`,
+//! - overridden for the iterators created within a closure with `with_threshold()`, or
+//! - measured on the current machine with `calibrate()`, which stores the result globally.
+//!
+//! Work runs on the global rayon thread pool, unless a `rayon::ThreadPool` is passed to one of
+//! the `..._in()` methods of `MultiFizzBuzz`. `pool_builder()` starts building a dedicated pool.
+//!
+//! ```
+//! # #[cfg(feature = "rayon")] {
+//! use fizzbuzz::{parallel, MultiFizzBuzz};
+//! use rayon::iter::ParallelIterator;
+//!
+//! assert_eq!(parallel::threshold(), parallel::DEFAULT_THRESHOLD);
+//! let answers: Vec<String> = parallel::with_threshold(1_000, || {
+//!     assert_eq!(parallel::threshold(), 1_000);
+//!     (1..5_000).fizzbuzz().collect() // split between threads
+//! });
+//! assert_eq!(answers[14], "fizzbuzz");
+//! # }
+//! ```
+
+#[cfg(feature = "rayon")]
+use std::time::{Duration, Instant};
+use std::{
+    cell::Cell,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+#[cfg(feature = "rayon")]
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
+
+#[cfg(feature = "rayon")]
+use crate::{FizzBuzz, FizzBuzzAnswer};
+
+/// The threshold used unless another is set: the size from which parallelisation makes sense
+/// for most machines
+pub const DEFAULT_THRESHOLD: usize = 300_000;
+
+/// The smallest and largest input lengths measured by `calibrate()`
+#[cfg(feature = "rayon")]
+const CALIBRATION_LENGTHS: (usize, usize) = (1 << 10, 1 << 21);
+
+/// The count of times each input length is measured by `calibrate()`, keeping the fastest
+#[cfg(feature = "rayon")]
+const CALIBRATION_RUNS: usize = 3;
+
+static THRESHOLD: AtomicUsize = AtomicUsize::new(DEFAULT_THRESHOLD);
+
+thread_local! {
+    static OVERRIDE: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// The count of elements from which inputs are split between threads on the current thread:
+/// set by the innermost `with_threshold()`, or else globally.
+pub fn threshold() -> usize {
+    OVERRIDE
+        .get()
+        .unwrap_or_else(|| THRESHOLD.load(Ordering::Relaxed))
+}
+
+/// Sets the global threshold, from which inputs are split between threads. Use `usize::MAX` to
+/// never parallelise or `0` to always parallelise.
+pub fn set_threshold(threshold: usize) {
+    THRESHOLD.store(threshold, Ordering::Relaxed);
+}
+
+/// Calls `f` with `threshold` in place of the global threshold for any iterators which it creates
+/// on the current thread.
+///
+/// The threshold is read when an iterator is created, so it applies to iterators which are
+/// returned from `f` and consumed later.
+pub fn with_threshold<R>(threshold: usize, f: impl FnOnce() -> R) -> R {
+    /// Restores the previous override, even if `f` panics
+    struct Restore(Option<usize>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            OVERRIDE.set(self.0);
+        }
+    }
+
+    let _restore = Restore(OVERRIDE.replace(Some(threshold)));
+    f()
+}
+
+/// Returns a builder for a dedicated rayon thread pool, with threads named `fizzbuzz-<index>`.
+///
+/// ```
+/// use fizzbuzz::{parallel, MultiFizzBuzz};
+///
+/// let pool = parallel::pool_builder().num_threads(1).build().unwrap();
+/// let answers: Vec<String> = (1..16).fizzbuzz_in(&pool);
+/// assert_eq!(answers[14], "fizzbuzz");
+/// ```
+#[cfg(feature = "rayon")]
+pub fn pool_builder() -> ThreadPoolBuilder {
+    ThreadPoolBuilder::new().thread_name(|index| format!("fizzbuzz-{index}"))
+}
+
+/// Runs `op` on `pool`, or on the current thread (and so the global pool, or the pool which it
+/// belongs to) if there is no `pool`
+#[cfg(feature = "rayon")]
+pub(crate) fn install<R: Send>(pool: Option<&ThreadPool>, op: impl FnOnce() -> R + Send) -> R {
+    match pool {
+        Some(pool) => pool.install(op),
+        None => op(),
+    }
+}
+
+/// The count of threads in `pool`, or in the current pool if there is no `pool`
+#[cfg(feature = "rayon")]
+pub(crate) fn num_threads(pool: Option<&ThreadPool>) -> usize {
+    pool.map_or_else(rayon::current_num_threads, ThreadPool::current_num_threads)
+}
+
+/// Measures the smallest input for which producing `Rtn` answers in parallel is faster than
+/// producing them sequentially on this machine, and stores it as the global threshold.
+///
+/// Returns the new threshold, which is `usize::MAX` if parallel evaluation was never faster (for
+/// example, because rayon has only one thread). Takes around a second with an optimised build.
+#[cfg(feature = "rayon")]
+pub fn calibrate<Rtn>() -> usize
+where
+    Rtn: From<FizzBuzzAnswer<usize>> + Send,
+{
+    let fastest = |run: &dyn Fn()| {
+        (0..CALIBRATION_RUNS)
+            .map(|_| {
+                let start = Instant::now();
+                run();
+                start.elapsed()
+            })
+            .min()
+            .unwrap_or(Duration::MAX)
+    };
+    let (shortest, longest) = CALIBRATION_LENGTHS;
+    // With a single thread, timing noise would be the only reason for parallel to win
+    let threads = rayon::current_num_threads();
+    let threshold = std::iter::successors(Some(shortest), |len| Some(len * 2))
+        .take_while(|&len| threads > 1 && len <= longest)
+        .find(|&len| {
+            let sequential = fastest(&|| {
+                let _: Vec<Rtn> = (0..len).map(|n| n.fizzbuzz().into()).collect();
+            });
+            let parallel = fastest(&|| {
+                let _: Vec<Rtn> = (0..len)
+                    .into_par_iter()
+                    .map(|n| n.fizzbuzz().into())
+                    .collect();
+            });
+            parallel < sequential
+        })
+        .unwrap_or(usize::MAX);
+    set_threshold(threshold);
+    threshold
+}
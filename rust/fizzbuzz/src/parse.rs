@@ -0,0 +1,524 @@
+//! Parse a `RuleSet` at runtime, from a compact text DSL or from TOML / JSON configuration.
+//!
+//! ## DSL
+//!
+//! Rules are given as comma-separated `divisor=word` pairs, optionally with a `@priority`,
+//! followed by any semicolon-separated `option=value` settings:
+//!
+//! ```
+//! use fizzbuzz::{FizzBuzz, RuleSet};
+//!
+//! let rules: RuleSet<i32> = "3=Fizz, 5=Buzz@1, 7=Woof; mode=join; separator=-".parse().unwrap();
+//! let answer: String = 105.fizzbuzz_with(&rules).into();
+//! assert_eq!(answer, "Fizz-Buzz-Woof");
+//! ```
+//!
+//! Options:
+//! - `mode`: how to combine the words when more than one rule applies, one of `concat`
+//!   (default), `join`, `first` or `priority`. See `rules::Combine`.
+//! - `separator`: the separator to use with `mode=join`.
+//! - `fallback`: a word to say when no rules apply, instead of the number.
+//!
+//! Whitespace around any token is ignored. Words and separators can be wrapped in
+//! double-quotes to preserve leading or trailing whitespace, or to include an `@` in a word.
+//!
+//! ## TOML & JSON
+//!
+//! With the `toml` or `json` features enabled, the same settings can be read from a document via
+//! `RuleSet::from_toml()` or `RuleSet::from_json()`:
+//!
+//! ```toml
+//! mode = "join"
+//! separator = "-"
+//!
+//! [[rules]]
+//! divisor = 3
+//! word = "Fizz"
+//!
+//! [[rules]]
+//! divisor = 5
+//! word = "Buzz"
+//! priority = 1
+//! ```
+//!
+//! ## Validation
+//!
+//! Every rule set is validated as it is parsed. A `ParseError` provides the line and column of
+//! the problem, for example a zero divisor, a duplicate divisor or an empty word.
+
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{fmt, str::FromStr};
+
+use crate::{rules::Combine, rules::Divisible, rules::Divisor, rules::Fallback, RuleSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A position in the parsed text. Both `line` and `column` start from `1`.
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    /// The position of the byte at `offset` in `input`.
+    fn at(input: &str, offset: usize) -> Self {
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+        Position {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The reason a rule set could not be parsed.
+pub enum ParseErrorKind {
+    /// The text is not valid DSL, TOML or JSON.
+    Syntax(String),
+    /// A divisor could not be parsed as a number.
+    InvalidDivisor(String),
+    /// A divisor is zero.
+    ZeroDivisor,
+    /// A divisor has already been used by an earlier rule.
+    DuplicateDivisor(String),
+    /// A rule has an empty word.
+    EmptyWord,
+    /// A priority could not be parsed as an `i32`.
+    InvalidPriority(String),
+    /// An option which is not `mode`, `separator` or `fallback`.
+    UnknownOption(String),
+    /// A mode which is not `concat`, `join`, `first` or `priority`.
+    UnknownMode(String),
+    /// `mode=join` without a `separator`, or a `separator` without `mode=join`.
+    Separator,
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::Syntax(message) => write!(f, "{message}"),
+            ParseErrorKind::InvalidDivisor(divisor) => write!(f, "invalid divisor `{divisor}`"),
+            ParseErrorKind::ZeroDivisor => write!(f, "divisor cannot be zero"),
+            ParseErrorKind::DuplicateDivisor(divisor) => {
+                write!(f, "duplicate divisor `{divisor}`")
+            }
+            ParseErrorKind::EmptyWord => write!(f, "word cannot be empty"),
+            ParseErrorKind::InvalidPriority(priority) => {
+                write!(f, "invalid priority `{priority}`")
+            }
+            ParseErrorKind::UnknownOption(option) => write!(f, "unknown option `{option}`"),
+            ParseErrorKind::UnknownMode(mode) => write!(
+                f,
+                "unknown mode `{mode}`, expected one of `concat`, `join`, `first` or `priority`"
+            ),
+            ParseErrorKind::Separator => {
+                write!(
+                    f,
+                    "a separator is required with, and only valid with, mode `join`"
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A rule set could not be parsed: provides the `kind` of problem and its `position`.
+pub struct ParseError {
+    pub position: Position,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}", self.kind, self.position)
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// A value, with the position at which it was found
+struct Located<T> {
+    value: T,
+    position: Position,
+}
+
+/// A single rule, before validation
+struct RuleSpec {
+    divisor: Located<String>,
+    word: Located<String>,
+    priority: Option<Located<String>>,
+}
+
+/// A full rule set, before validation
+#[derive(Default)]
+struct Spec {
+    rules: Vec<RuleSpec>,
+    mode: Option<Located<String>>,
+    separator: Option<Located<String>>,
+    fallback: Option<Located<String>>,
+}
+
+impl Spec {
+    /// Validates the specification and builds a `RuleSet`
+    fn build<Num>(self) -> Result<RuleSet<Num>, ParseError>
+    where
+        Num: FromStr + TryFrom<u8> + PartialEq + Clone + Send + Sync + Divisor + 'static,
+        for<'a> &'a Num: core::ops::Rem<Num, Output = Num>,
+    {
+        let error = |position, kind| ParseError { position, kind };
+        let zero = <Num>::try_from(0_u8).ok();
+        let mut divisors: Vec<Num> = vec![];
+        let mut rules = RuleSet::new();
+        for rule in self.rules {
+            let Located { value, position } = rule.divisor;
+            let divisor: Num = value
+                .parse()
+                .map_err(|_| error(position, ParseErrorKind::InvalidDivisor(value.clone())))?;
+            if zero.as_ref() == Some(&divisor) {
+                return Err(error(position, ParseErrorKind::ZeroDivisor));
+            }
+            if divisors.contains(&divisor) {
+                return Err(error(position, ParseErrorKind::DuplicateDivisor(value)));
+            }
+            if rule.word.value.is_empty() {
+                return Err(error(rule.word.position, ParseErrorKind::EmptyWord));
+            }
+            let priority = match rule.priority {
+                None => 0,
+                Some(Located { value, position }) => value
+                    .parse()
+                    .map_err(|_| error(position, ParseErrorKind::InvalidPriority(value)))?,
+            };
+            divisors.push(divisor.clone());
+            rules = rules.rule_with_priority(Divisible::new(divisor), rule.word.value, priority);
+        }
+
+        let combine = match (self.mode, self.separator) {
+            (None, None) => Combine::Concat,
+            (None, Some(separator)) => {
+                return Err(error(separator.position, ParseErrorKind::Separator))
+            }
+            (Some(mode), separator) => match (mode.value.as_str(), separator) {
+                ("join", Some(separator)) => Combine::Join(separator.value.into()),
+                ("join", None) => return Err(error(mode.position, ParseErrorKind::Separator)),
+                ("concat" | "first" | "priority", Some(separator)) => {
+                    return Err(error(separator.position, ParseErrorKind::Separator))
+                }
+                ("concat", None) => Combine::Concat,
+                ("first", None) => Combine::FirstMatch,
+                ("priority", None) => Combine::HighestPriority,
+                (_, _) => {
+                    return Err(error(
+                        mode.position,
+                        ParseErrorKind::UnknownMode(mode.value),
+                    ))
+                }
+            },
+        };
+
+        if let Some(fallback) = self.fallback {
+            if fallback.value.is_empty() {
+                return Err(error(fallback.position, ParseErrorKind::EmptyWord));
+            }
+            rules = rules.fallback(Fallback::Word(fallback.value.into()))
+        }
+
+        Ok(rules.combine(combine))
+    }
+}
+
+/// Splits `text`, found at `offset` in the input, at each `separator`.
+/// Returns each part with its own offset.
+fn split(text: &str, offset: usize, separator: char) -> impl Iterator<Item = (&str, usize)> {
+    let mut start = offset;
+    text.split(separator).map(move |part| {
+        let part_offset = start;
+        start += part.len() + separator.len_utf8();
+        (part, part_offset)
+    })
+}
+
+/// Trims whitespace from `text`, found at `offset`.
+fn trim(text: &str, offset: usize) -> (&str, usize) {
+    let trimmed = text.trim_start();
+    (trimmed.trim_end(), offset + text.len() - trimmed.len())
+}
+
+/// Trims whitespace from `text`, found at `offset`, and removes any surrounding double-quotes.
+fn value(text: &str, offset: usize) -> (&str, usize) {
+    let (trimmed, offset) = trim(text, offset);
+    match trimmed
+        .strip_prefix('"')
+        .and_then(|text| text.strip_suffix('"'))
+    {
+        Some(unquoted) => (unquoted, offset + 1),
+        None => (trimmed, offset),
+    }
+}
+
+/// The index of the `@` which separates a `word` from its priority, if any.
+/// An `@` within a quoted word is part of the word.
+fn priority_at(word: &str) -> Option<usize> {
+    let unquoted = match word.trim_start().strip_prefix('"') {
+        Some(quoted) => {
+            let start = word.len() - quoted.len();
+            start + quoted.find('"')? + 1
+        }
+        None => 0,
+    };
+    word[unquoted..].rfind('@').map(|at| unquoted + at)
+}
+
+/// Parses the DSL into a `Spec`, checking only the syntax
+fn parse_dsl(input: &str) -> Result<Spec, ParseError> {
+    let locate = |value: &str, offset| Located {
+        value: value.to_string(),
+        position: Position::at(input, offset),
+    };
+    let syntax_error = |offset, message: &str| ParseError {
+        position: Position::at(input, offset),
+        kind: ParseErrorKind::Syntax(message.to_string()),
+    };
+
+    let mut spec = Spec::default();
+    let mut sections = split(input, 0, ';');
+    let (rules, rules_offset) = sections.next().unwrap_or((input, 0));
+    if !rules.trim().is_empty() {
+        for (rule, offset) in split(rules, rules_offset, ',') {
+            let (rule, offset) = trim(rule, offset);
+            if rule.is_empty() {
+                return Err(syntax_error(offset, "expected a rule `divisor=word`"));
+            }
+            let Some((divisor, word)) = rule.split_once('=') else {
+                return Err(syntax_error(offset + rule.len(), "expected `=`"));
+            };
+            let word_offset = offset + divisor.len() + 1;
+            let (word, priority) = match priority_at(word) {
+                Some(at) => {
+                    let priority_offset = word_offset + at + 1;
+                    let (priority, priority_offset) = trim(&word[at + 1..], priority_offset);
+                    (&word[..at], Some(locate(priority, priority_offset)))
+                }
+                None => (word, None),
+            };
+            let (divisor, divisor_offset) = trim(divisor, offset);
+            let (word, word_offset) = value(word, word_offset);
+            spec.rules.push(RuleSpec {
+                divisor: locate(divisor, divisor_offset),
+                word: locate(word, word_offset),
+                priority,
+            });
+        }
+    }
+
+    for (option, offset) in sections {
+        let (option, offset) = trim(option, offset);
+        if option.is_empty() {
+            continue; // Allow a trailing `;`
+        }
+        let Some((key, value)) = option.split_once('=') else {
+            return Err(syntax_error(offset + option.len(), "expected `=`"));
+        };
+        let (value, value_offset) = self::value(value, offset + key.len() + 1);
+        let value = Some(locate(value, value_offset));
+        match key.trim() {
+            "mode" => spec.mode = value,
+            "separator" => spec.separator = value,
+            "fallback" => spec.fallback = value,
+            unknown => {
+                return Err(ParseError {
+                    position: Position::at(input, offset),
+                    kind: ParseErrorKind::UnknownOption(unknown.to_string()),
+                })
+            }
+        }
+    }
+    Ok(spec)
+}
+
+/// Parses a rule set from the DSL.
+impl<Num> FromStr for RuleSet<Num>
+where
+    Num: FromStr + TryFrom<u8> + PartialEq + Clone + Send + Sync + Divisor + 'static,
+    for<'a> &'a Num: core::ops::Rem<Num, Output = Num>,
+{
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        parse_dsl(input)?.build()
+    }
+}
+
+#[cfg(feature = "toml")]
+mod toml_config {
+    use serde::Deserialize;
+    use toml::Spanned;
+
+    use super::*;
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Config {
+        #[serde(default)]
+        rules: Vec<Rule>,
+        mode: Option<Spanned<String>>,
+        separator: Option<Spanned<String>>,
+        fallback: Option<Spanned<String>>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Rule {
+        divisor: Spanned<toml::Value>,
+        word: Spanned<String>,
+        priority: Option<Spanned<toml::Value>>,
+    }
+
+    /// Parses the TOML document into a `Spec`, checking only the syntax
+    pub(super) fn parse(input: &str) -> Result<Spec, ParseError> {
+        let locate = |value: Spanned<String>| Located {
+            position: Position::at(input, value.span().start),
+            value: value.into_inner(),
+        };
+        let number = |value: Spanned<toml::Value>| {
+            let position = Position::at(input, value.span().start);
+            let value = match value.into_inner() {
+                toml::Value::String(value) => value,
+                value => value.to_string(),
+            };
+            Located { value, position }
+        };
+
+        let config: Config = toml::from_str(input).map_err(|e| ParseError {
+            position: Position::at(input, e.span().map_or(0, |span| span.start)),
+            kind: ParseErrorKind::Syntax(e.message().to_string()),
+        })?;
+        Ok(Spec {
+            rules: config
+                .rules
+                .into_iter()
+                .map(|rule| RuleSpec {
+                    divisor: number(rule.divisor),
+                    word: locate(rule.word),
+                    priority: rule.priority.map(number),
+                })
+                .collect(),
+            mode: config.mode.map(locate),
+            separator: config.separator.map(locate),
+            fallback: config.fallback.map(locate),
+        })
+    }
+}
+
+#[cfg(feature = "json")]
+mod json_config {
+    use serde::Deserialize;
+    use serde_json::value::RawValue;
+
+    use super::*;
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Config<'a> {
+        #[serde(borrow, default)]
+        rules: Vec<Rule<'a>>,
+        #[serde(borrow)]
+        mode: Option<&'a RawValue>,
+        #[serde(borrow)]
+        separator: Option<&'a RawValue>,
+        #[serde(borrow)]
+        fallback: Option<&'a RawValue>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Rule<'a> {
+        #[serde(borrow)]
+        divisor: &'a RawValue,
+        #[serde(borrow)]
+        word: &'a RawValue,
+        #[serde(borrow)]
+        priority: Option<&'a RawValue>,
+    }
+
+    fn syntax_error(error: serde_json::Error) -> ParseError {
+        let message = error.to_string();
+        let message = match message.rsplit_once(" at line ") {
+            Some((message, _)) => message.to_string(),
+            None => message,
+        };
+        ParseError {
+            position: Position {
+                line: error.line(),
+                column: error.column(),
+            },
+            kind: ParseErrorKind::Syntax(message),
+        }
+    }
+
+    /// Parses the JSON document into a `Spec`, checking only the syntax
+    pub(super) fn parse(input: &str) -> Result<Spec, ParseError> {
+        // `RawValue`s borrow from `input`, which gives us their position
+        let position = |raw: &RawValue| {
+            Position::at(input, raw.get().as_ptr() as usize - input.as_ptr() as usize)
+        };
+        let string = |raw: &RawValue| -> Result<Located<String>, ParseError> {
+            let value: String = serde_json::from_str(raw.get()).map_err(|e| ParseError {
+                position: position(raw),
+                kind: ParseErrorKind::Syntax(syntax_error(e).kind.to_string()),
+            })?;
+            Ok(Located {
+                value,
+                position: position(raw),
+            })
+        };
+        let number = |raw: &RawValue| Located {
+            value: serde_json::from_str(raw.get()).unwrap_or_else(|_| raw.get().to_string()),
+            position: position(raw),
+        };
+
+        let config: Config = serde_json::from_str(input).map_err(syntax_error)?;
+        let mut rules = vec![];
+        for rule in config.rules {
+            rules.push(RuleSpec {
+                divisor: number(rule.divisor),
+                word: string(rule.word)?,
+                priority: rule.priority.map(number),
+            });
+        }
+        Ok(Spec {
+            rules,
+            mode: config.mode.map(string).transpose()?,
+            separator: config.separator.map(string).transpose()?,
+            fallback: config.fallback.map(string).transpose()?,
+        })
+    }
+}
+
+impl<Num> RuleSet<Num>
+where
+    Num: FromStr + TryFrom<u8> + PartialEq + Clone + Send + Sync + Divisor + 'static,
+    for<'a> &'a Num: core::ops::Rem<Num, Output = Num>,
+{
+    /// Parses a rule set from a TOML document.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(input: &str) -> Result<Self, ParseError> {
+        toml_config::parse(input)?.build()
+    }
+
+    /// Parses a rule set from a JSON document.
+    #[cfg(feature = "json")]
+    pub fn from_json(input: &str) -> Result<Self, ParseError> {
+        json_config::parse(input)?.build()
+    }
+}
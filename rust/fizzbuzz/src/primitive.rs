@@ -0,0 +1,97 @@
+//! Decimal formatting of primitive integers into a stack buffer, for rendering ranges.
+//!
+//! There are no dedicated implementations of `FizzBuzz` for primitive integers: choosing them from
+//! the blanket implementation would need the `TypeId` of `Num`, and so `Num: 'static`. The blanket
+//! implementation is no slower for them, as its `TryFrom<u8>` conversions are resolved at compile
+//! time once monomorphised, and `Display` already formats through a stack buffer.
+
+/// Enough bytes for any primitive integer: `i128::MIN` has 39 digits and a sign
+pub(crate) const MAX_DIGITS: usize = 40;
+
+/// `"00"` to `"99"`, for formatting two digits at a time
+const DIGIT_PAIRS: [u8; 200] = {
+    let mut pairs = [0; 200];
+    let mut i = 0;
+    while i < 100 {
+        pairs[2 * i] = b'0' + (i / 10) as u8;
+        pairs[2 * i + 1] = b'0' + (i % 10) as u8;
+        i += 1;
+    }
+    pairs
+};
+
+/// Implemented for each primitive integer type, and sealed within the crate.
+pub(crate) trait Primitive: Copy {
+    /// Writes the decimal representation to the end of `buf`, returning it
+    fn format(self, buf: &mut [u8; MAX_DIGITS]) -> &str;
+}
+
+macro_rules! impl_primitive {
+    ($($t:ty),*) => {$(
+        impl Primitive for $t {
+            #[allow(unused_comparisons)]
+            fn format(self, buf: &mut [u8; MAX_DIGITS]) -> &str {
+                let mut magnitude = self.abs_diff(0);
+                let mut start = MAX_DIGITS;
+                while magnitude >= 100 {
+                    let pair = 2 * (magnitude % 100) as usize;
+                    magnitude /= 100;
+                    start -= 2;
+                    buf[start..start + 2].copy_from_slice(&DIGIT_PAIRS[pair..pair + 2]);
+                }
+                if magnitude >= 10 {
+                    let pair = 2 * magnitude as usize;
+                    start -= 2;
+                    buf[start..start + 2].copy_from_slice(&DIGIT_PAIRS[pair..pair + 2]);
+                } else {
+                    start -= 1;
+                    buf[start] = b'0' + magnitude as u8;
+                }
+                if self < 0 {
+                    start -= 1;
+                    buf[start] = b'-';
+                }
+                core::str::from_utf8(&buf[start..]).expect("digits are ascii")
+            }
+        }
+    )*};
+}
+
+impl_primitive!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn format_matches_display() {
+        let mut buf = [0; MAX_DIGITS];
+        for n in [
+            i128::MIN,
+            i128::MIN + 1,
+            -100,
+            -99,
+            -10,
+            -9,
+            -1,
+            0,
+            1,
+            9,
+            10,
+            99,
+            100,
+            i128::MAX,
+        ] {
+            assert_eq!(n.format(&mut buf), n.to_string());
+        }
+        for n in [0, 7, 10, 99, 100, 1_000, 12_345, u128::MAX] {
+            assert_eq!(n.format(&mut buf), n.to_string());
+        }
+        for n in i8::MIN..=i8::MAX {
+            assert_eq!(n.format(&mut buf), n.to_string());
+        }
+        for n in u16::MIN..=u16::MAX {
+            assert_eq!(n.format(&mut buf), n.to_string());
+        }
+    }
+}
@@ -0,0 +1,380 @@
+//! Fast paths for ranges of primitive integers.
+//!
+//! Any rule set made up only of divisors is periodic, with a period of the lowest common multiple
+//! of the divisors: 15 for the classic game. Rather than performing a `%` for every divisor and
+//! every number, `RangeFizzBuzz` tabulates the answers for one period (a "wheel") and walks
+//! around it with a counter.
+//!
+//! If the period is too large to tabulate, longer than the range, or the `RuleSet` contains rules
+//! which are not divisors, each number is evaluated individually, exactly as `MultiFizzBuzz` does.
+//!
+//! Large divisors quickly make the period too large for a wheel. For these, `fizzbuzz_sieve_with()`
+//! marks the multiples of each divisor in a bitmap for each chunk of the range, in the style of the
+//! sieve of Eratosthenes, doing work proportional to `Σ 1/divisor` for each number rather than one
+//! `%` per divisor.
+//!
+//! ```
+//! use fizzbuzz::RuleSet;
+//! use fizzbuzz::range::RangeFizzBuzz;
+//! use rayon::iter::ParallelIterator;
+//!
+//! let answers: Vec<String> = (1..16_u32).fizzbuzz_wheel().collect();
+//! assert_eq!(answers[14], "fizzbuzz");
+//!
+//! let rules = RuleSet::default().divisor(7, "woof");
+//! let answers: Vec<String> = (1..=105_u32).fizzbuzz_wheel_with(&rules).collect();
+//! assert_eq!(answers[104], "fizzbuzzwoof");
+//!
+//! let rules = RuleSet::new().divisor(1_000_003, "big").divisor(1_000_033, "bigger");
+//! let answers: Vec<String> = (1_000_000..2_000_000_u32).fizzbuzz_sieve_with(&rules).collect();
+//! assert_eq!(answers[3], "big");
+//! assert_eq!(answers[33], "bigger");
+//! ```
+//!
+//! `ParRange` provides the `IndexedParallelIterator` which rayon lacks for `RangeInclusive`s and
+//! ranges of 64- and 128-bit integers, so `MultiFizzBuzz` can evaluate them, stepped or reversed:
+//!
+//! ```
+//! use fizzbuzz::range::ParRange;
+//! use fizzbuzz::MultiFizzBuzz;
+//! use rayon::iter::ParallelIterator;
+//!
+//! let answers: Vec<String> = ParRange::from(i64::MAX - 2..=i64::MAX).fizzbuzz().collect();
+//! assert_eq!(answers, vec!["buzz", "fizz", "9223372036854775807"]);
+//! ```
+
+use std::{
+    io,
+    ops::{Range, RangeInclusive},
+};
+
+use rayon::iter::Either;
+use rayon::prelude::*;
+
+use crate::integer::Integer;
+use crate::{FizzBuzz, FizzBuzzAnswer, RuleSet};
+
+mod indexed;
+mod lines;
+mod slice;
+
+pub use indexed::{ParRange, Subranges};
+pub use slice::{SliceSpec, ZeroStepError};
+
+/// The largest period which will be tabulated
+const MAX_PERIOD: usize = 1 << 16;
+
+/// The period of the classic game
+const CLASSIC_PERIOD: usize = 15;
+
+/// The most rules which can be sieved: one bit each in a `u64`
+const MAX_SIEVED_RULES: usize = u64::BITS as usize;
+
+/// The count of numbers sieved at once by each rayon job
+const SIEVE_WINDOW: usize = 1 << 12;
+
+const TOO_LONG: &str = "range contains more than usize::MAX numbers";
+
+/// Used to obtain the answers for a range of primitive integers without evaluating every
+/// divisor for every number.
+///
+/// Implemented for `Range` and `RangeInclusive` of all primitive integer types.
+///
+/// # Panics
+/// If the range contains more than `usize::MAX` numbers.
+pub trait RangeFizzBuzz {
+    /// The type of the numbers in the range
+    type Num;
+
+    /// Returns the same answers as `MultiFizzBuzz::fizzbuzz()`, walking a wheel of the answers
+    /// for one period of the classic game.
+    fn fizzbuzz_wheel<Rtn>(self) -> impl IndexedParallelIterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send;
+
+    /// Returns the same answers as `MultiFizzBuzz::fizzbuzz_with()`, walking a wheel of the
+    /// answers for one period of the `RuleSet`, if it is periodic.
+    fn fizzbuzz_wheel_with<Rtn>(
+        self,
+        rules: &RuleSet<Self::Num>,
+    ) -> impl IndexedParallelIterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send;
+
+    /// Returns the same answers as `MultiFizzBuzz::fizzbuzz_with()`, sieving each chunk of the
+    /// range for multiples of each divisor if every rule in the `RuleSet` is a divisor.
+    fn fizzbuzz_sieve_with<Rtn>(
+        self,
+        rules: &RuleSet<Self::Num>,
+    ) -> impl IndexedParallelIterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send;
+
+    /// Returns the output of the classic game for the range as contiguous blocks of bytes, with
+    /// each answer followed by `\n`.
+    ///
+    /// Each block holds the lines for a chunk of the range, beginning with a number one more than
+    /// a multiple of 15. Concatenating the blocks in order gives the complete output.
+    fn fizzbuzz_lines(self) -> impl IndexedParallelIterator<Item = Vec<u8>>;
+
+    /// Writes the output of the classic game for the range to `out`, with each answer followed by
+    /// `\n`.
+    ///
+    /// Chunks of the range are rendered in parallel into reusable buffers and written in order,
+    /// with one large write for each chunk. The sink is not flushed.
+    fn write_fizzbuzz_lines(self, out: &mut impl io::Write) -> io::Result<()>;
+
+    /// As `write_fizzbuzz_lines()`, but if `out` is a pipe, the rendered buffers are mapped into
+    /// the pipe with `vmsplice` instead of being copied. Otherwise behaves exactly as
+    /// `write_fizzbuzz_lines()`.
+    ///
+    /// `out` is flushed before writing starts, and this function only returns once the reader
+    /// has emptied the pipe. If the reader reads nothing for a second, it returns anyway and the
+    /// memory still mapped into the pipe is leaked rather than reused. A pipe with a capacity of
+    /// more than 256KiB is reduced to 256KiB while writing, and then restored.
+    ///
+    /// The reader must _read_ from the pipe: if it moves the data onwards with `splice` or
+    /// `tee`, the pages it receives may already hold later output.
+    #[cfg(target_os = "linux")]
+    fn splice_fizzbuzz_lines(
+        self,
+        out: &mut (impl io::Write + std::os::fd::AsFd),
+    ) -> io::Result<()>;
+}
+
+/// The first number in a range and the count of numbers in it
+trait Bounds {
+    type Num;
+
+    fn bounds(self) -> (Self::Num, usize);
+}
+
+impl<Num: Integer> Bounds for Range<Num> {
+    type Num = Num;
+
+    fn bounds(self) -> (Num, usize) {
+        if self.start >= self.end {
+            return (self.start, 0);
+        }
+        (self.start, self.end.distance(self.start).expect(TOO_LONG))
+    }
+}
+
+impl<Num: Integer> Bounds for RangeInclusive<Num> {
+    type Num = Num;
+
+    fn bounds(self) -> (Num, usize) {
+        if self.is_empty() {
+            return (*self.start(), 0);
+        }
+        let len = self
+            .end()
+            .distance(*self.start())
+            .and_then(|distance| distance.checked_add(1))
+            .expect(TOO_LONG);
+        (*self.start(), len)
+    }
+}
+
+/// Position on the wheel of the last number evaluated by a rayon job
+struct Cursor {
+    next: usize,
+    position: usize,
+}
+
+/// Lazily evaluates the answers for the `len` numbers from `start`, using a wheel of `period`
+/// answers if possible.
+fn walk<'a, Num, Rtn>(
+    start: Num,
+    len: usize,
+    period: Option<usize>,
+    answer: impl Fn(&Num) -> FizzBuzzAnswer<Num> + Send + Sync + 'a,
+) -> impl IndexedParallelIterator<Item = Rtn> + 'a
+where
+    Num: Integer + 'a,
+    Rtn: From<FizzBuzzAnswer<Num>> + Send,
+{
+    let indices = (0..len).into_par_iter().with_min_len(crate::min_len(len));
+    let Some(period) = period.filter(|&period| period <= len) else {
+        return Either::Right(indices.map(move |i| answer(&start.nth(i)).into()));
+    };
+    // `None` stands for a `FizzBuzzAnswer::Number`, which holds each number
+    let wheel: Vec<Option<FizzBuzzAnswer<Num>>> = (0..period)
+        .map(|i| match answer(&start.nth(i)) {
+            FizzBuzzAnswer::Number(_) => None,
+            answer => Some(answer),
+        })
+        .collect();
+    Either::Left(indices.map_init(
+        || Cursor {
+            next: usize::MAX,
+            position: 0,
+        },
+        move |cursor, i| {
+            cursor.position = if i == cursor.next {
+                match cursor.position + 1 {
+                    position if position == period => 0,
+                    position => position,
+                }
+            } else {
+                i % period
+            };
+            cursor.next = i + 1;
+            match &wheel[cursor.position] {
+                Some(answer) => answer.clone(),
+                None => FizzBuzzAnswer::Number(start.nth(i)),
+            }
+            .into()
+        },
+    ))
+}
+
+/// The numbers, and rules which apply to each of them, sieved by a rayon job
+struct Window {
+    first: usize,
+    applies: Vec<u64>,
+}
+
+/// Lazily evaluates the answers for the `len` numbers from `start`, sieving windows of numbers
+/// for multiples of each rule's period if possible.
+fn sieve<'a, Num, Rtn>(
+    start: Num,
+    len: usize,
+    rules: &'a RuleSet<Num>,
+) -> impl IndexedParallelIterator<Item = Rtn> + 'a
+where
+    Num: Integer + 'a,
+    Rtn: From<FizzBuzzAnswer<Num>> + Send,
+{
+    let indices = (0..len).into_par_iter().with_min_len(crate::min_len(len));
+    let periods = rules.periods().filter(|periods| {
+        periods.len() <= MAX_SIEVED_RULES && periods.iter().all(|period| period.magnitude() != 0)
+    });
+    let Some(periods) = periods else {
+        return Either::Right(indices.map(move |i| rules.answer(&start.nth(i)).into()));
+    };
+    let periods: Vec<Num> = periods.into_iter().copied().collect();
+    Either::Left(indices.map_init(
+        || Window {
+            first: usize::MAX,
+            applies: Vec::with_capacity(SIEVE_WINDOW),
+        },
+        move |window, i| {
+            if !(window.first..window.first.saturating_add(window.applies.len())).contains(&i) {
+                window.first = i;
+                window.applies.clear();
+                window.applies.resize(SIEVE_WINDOW.min(len - i), 0);
+                let first = start.nth(i);
+                for (rule, &period) in periods.iter().enumerate() {
+                    let step = usize::try_from(period.magnitude()).unwrap_or(usize::MAX);
+                    let Ok(offset) = usize::try_from(first.to_multiple(period)) else {
+                        continue;
+                    };
+                    for offset in (offset..window.applies.len()).step_by(step) {
+                        window.applies[offset] |= 1 << rule;
+                    }
+                }
+            }
+            let applies = window.applies[i - window.first];
+            rules.answer_for_rules(&start.nth(i), bits(applies)).into()
+        },
+    ))
+}
+
+/// The indices of the set bits in `mask`, from least significant
+fn bits(mut mask: u64) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        (mask != 0).then(|| {
+            let bit = mask.trailing_zeros() as usize;
+            mask &= mask - 1;
+            bit
+        })
+    })
+}
+
+/// The period of `rules`, if they are periodic and the period is small enough to tabulate
+fn period<Num: Integer>(rules: &RuleSet<Num>) -> Option<usize> {
+    fn gcd(a: usize, b: usize) -> usize {
+        match b {
+            0 => a,
+            b => gcd(b, a % b),
+        }
+    }
+
+    rules
+        .periods()?
+        .into_iter()
+        .try_fold(1_usize, |lcm, period| {
+            let period = usize::try_from(period.magnitude())
+                .ok()
+                .filter(|&period| period != 0)?;
+            let lcm = (lcm / gcd(lcm, period)).checked_mul(period)?;
+            (lcm <= MAX_PERIOD).then_some(lcm)
+        })
+}
+
+macro_rules! impl_range_fizzbuzz {
+    ($range:ident: $($t:ty),*) => {$(
+        impl RangeFizzBuzz for $range<$t> {
+            type Num = $t;
+
+            fn fizzbuzz_wheel<Rtn>(self) -> impl IndexedParallelIterator<Item = Rtn>
+            where
+                Rtn: From<FizzBuzzAnswer<$t>> + Send,
+            {
+                let (start, len) = self.bounds();
+                walk(start, len, Some(CLASSIC_PERIOD), |n: &$t| n.fizzbuzz())
+            }
+
+            fn fizzbuzz_wheel_with<Rtn>(
+                self,
+                rules: &RuleSet<$t>,
+            ) -> impl IndexedParallelIterator<Item = Rtn>
+            where
+                Rtn: From<FizzBuzzAnswer<$t>> + Send,
+            {
+                let (start, len) = self.bounds();
+                walk(start, len, period(rules), |n: &$t| n.fizzbuzz_with(rules))
+            }
+
+            fn fizzbuzz_sieve_with<Rtn>(
+                self,
+                rules: &RuleSet<$t>,
+            ) -> impl IndexedParallelIterator<Item = Rtn>
+            where
+                Rtn: From<FizzBuzzAnswer<$t>> + Send,
+            {
+                let (start, len) = self.bounds();
+                sieve(start, len, rules)
+            }
+
+            fn fizzbuzz_lines(self) -> impl IndexedParallelIterator<Item = Vec<u8>> {
+                let (start, len) = self.bounds();
+                let chunks = lines::Chunks::new(start, len);
+                (0..chunks.count).into_par_iter().map(move |k| {
+                    let mut buffer = Vec::new();
+                    chunks.render(k, &mut buffer);
+                    buffer
+                })
+            }
+
+            fn write_fizzbuzz_lines(self, out: &mut impl io::Write) -> io::Result<()> {
+                let (start, len) = self.bounds();
+                lines::write(lines::Chunks::new(start, len), out)
+            }
+
+            #[cfg(target_os = "linux")]
+            fn splice_fizzbuzz_lines(
+                self,
+                out: &mut (impl io::Write + std::os::fd::AsFd),
+            ) -> io::Result<()> {
+                out.flush()?;
+                let (start, len) = self.bounds();
+                lines::splice(lines::Chunks::new(start, len), out)
+            }
+        }
+    )*};
+}
+
+impl_range_fizzbuzz!(Range: u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+impl_range_fizzbuzz!(RangeInclusive: u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
@@ -0,0 +1,286 @@
+//! Indexed parallel iteration over ranges of any primitive integer type.
+//!
+//! rayon only provides an `IndexedParallelIterator` for `Range`s of types up to 32 bits wide, so
+//! `MultiFizzBuzz` cannot evaluate `RangeInclusive`s or ranges of `i64`, `u64`, `i128` and `u128`
+//! directly. A `ParRange` can be created from any of these, stepped and reversed, and split into
+//! subranges which each contain no more than `usize::MAX` numbers.
+//!
+//! The numbers in a range are found from its first number and a step, using wrapping arithmetic,
+//! which is exact for every number within the range, so ranges can reach the bounds of their type.
+
+use std::ops::{Range, RangeInclusive};
+
+use rayon::iter::plumbing::{bridge, Consumer, Producer, ProducerCallback, UnindexedConsumer};
+use rayon::prelude::*;
+
+use super::{Integer, TOO_LONG};
+
+/// A range of primitive integers which can be evaluated as an `IndexedParallelIterator`, for
+/// example with `MultiFizzBuzz`.
+///
+/// Created from a `Range` or `RangeInclusive` of any primitive integer type with `.into()` or
+/// `ParRange::from()`:
+///
+/// ```
+/// use fizzbuzz::range::ParRange;
+/// use fizzbuzz::MultiFizzBuzz;
+/// use rayon::iter::ParallelIterator;
+///
+/// let answers: Vec<String> = ParRange::from(1..=15_u64).rev().step_by(2).fizzbuzz().collect();
+/// assert_eq!(answers, vec!["fizzbuzz", "13", "11", "fizz", "7", "buzz", "fizz", "1"]);
+///
+/// let mut subranges = ParRange::from(1..=u64::MAX).subranges(1 << 20);
+/// let answers: Vec<String> = subranges.nth(1).unwrap().fizzbuzz().collect();
+/// assert_eq!(answers[0], "1048577");
+/// ```
+///
+/// # Panics
+/// Evaluating a range which contains more than `usize::MAX` numbers panics. Split it with
+/// `subranges()` first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParRange<Num> {
+    first: Num,
+    /// The distance between consecutive numbers
+    step: u128,
+    /// `true` if each number is smaller than the one before
+    descending: bool,
+    /// The count of steps from the first to the last number, or `None` if the range is empty
+    last: Option<u128>,
+}
+
+impl<Num: Integer> ParRange<Num> {
+    /// The `last + 1` numbers `first, first ± step, first ± 2 * step, ...`, or no numbers if
+    /// `last` is `None`. Every number must be within the bounds of `Num`.
+    pub(super) fn new(first: Num, step: u128, descending: bool, last: Option<u128>) -> Self {
+        Self {
+            first,
+            step,
+            descending,
+            last,
+        }
+    }
+
+    /// The count of numbers in the range, if it fits into a `usize`
+    pub fn checked_len(&self) -> Option<usize> {
+        match self.last {
+            Some(last) => usize::try_from(last).ok()?.checked_add(1),
+            None => Some(0),
+        }
+    }
+
+    /// The same numbers in the opposite order.
+    ///
+    /// Unlike `IndexedParallelIterator::rev()`, this returns a `ParRange`, and can be used on
+    /// ranges of any length.
+    pub fn rev(self) -> Self {
+        let Some(last) = self.last else {
+            return self;
+        };
+        Self {
+            first: self.first.offset(last * self.step, self.descending),
+            descending: !self.descending,
+            ..self
+        }
+    }
+
+    /// Every `step`th number, starting with the first.
+    ///
+    /// Unlike `IndexedParallelIterator::step_by()`, this returns a `ParRange`, and can be used on
+    /// ranges of any length.
+    ///
+    /// # Panics
+    /// If `step` is zero.
+    pub fn step_by(self, step: usize) -> Self {
+        assert!(step > 0, "step cannot be zero");
+        let step = step as u128;
+        Self {
+            // Only saturates if there is at most one number left, which makes the step irrelevant
+            step: self.step.saturating_mul(step),
+            last: self.last.map(|last| last / step),
+            ..self
+        }
+    }
+
+    /// The first `index` numbers, and the numbers which follow them.
+    ///
+    /// # Panics
+    /// If `index` is greater than the count of numbers in the range.
+    pub fn split_at(self, index: usize) -> (Self, Self) {
+        let index = index as u128;
+        let head_last = index.checked_sub(1);
+        match self.last {
+            Some(last) if index <= last => {
+                let head = Self {
+                    last: head_last,
+                    ..self
+                };
+                let tail = Self {
+                    first: self.first.offset(index * self.step, self.descending),
+                    last: Some(last - index),
+                    ..self
+                };
+                (head, tail)
+            }
+            Some(last) if index == last + 1 => (self, Self { last: None, ..self }),
+            None if index == 0 => (self, self),
+            _ => panic!("split index is out of bounds"),
+        }
+    }
+
+    /// Splits the range into consecutive subranges of `len` numbers, the last of which may be
+    /// shorter, so that ranges containing more than `usize::MAX` numbers can be evaluated one
+    /// subrange at a time.
+    ///
+    /// # Panics
+    /// If `len` is zero.
+    pub fn subranges(self, len: usize) -> Subranges<Num> {
+        assert!(len > 0, "subranges cannot be empty");
+        Subranges { rest: self, len }
+    }
+}
+
+impl<Num: Integer> From<Range<Num>> for ParRange<Num> {
+    fn from(range: Range<Num>) -> Self {
+        Self {
+            first: range.start,
+            step: 1,
+            descending: false,
+            last: (range.start < range.end).then(|| range.end.span(range.start) - 1),
+        }
+    }
+}
+
+impl<Num: Integer> From<RangeInclusive<Num>> for ParRange<Num> {
+    fn from(range: RangeInclusive<Num>) -> Self {
+        Self {
+            first: *range.start(),
+            step: 1,
+            descending: false,
+            last: (!range.is_empty()).then(|| range.end().span(*range.start())),
+        }
+    }
+}
+
+impl<Num: Integer> ParallelIterator for ParRange<Num> {
+    type Item = Num;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        self.checked_len()
+    }
+}
+
+impl<Num: Integer> IndexedParallelIterator for ParRange<Num> {
+    fn len(&self) -> usize {
+        self.checked_len().expect(TOO_LONG)
+    }
+
+    fn drive<C: Consumer<Self::Item>>(self, consumer: C) -> C::Result {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB: ProducerCallback<Self::Item>>(self, callback: CB) -> CB::Output {
+        callback.callback(Steps {
+            first: self.first,
+            step: self.step,
+            descending: self.descending,
+            len: self.len(),
+        })
+    }
+}
+
+/// Consecutive subranges of a `ParRange`, created by `ParRange::subranges()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subranges<Num> {
+    rest: ParRange<Num>,
+    len: usize,
+}
+
+impl<Num: Integer> Iterator for Subranges<Num> {
+    type Item = ParRange<Num>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let last = self.rest.last?;
+        let remaining = usize::try_from(last)
+            .ok()
+            .and_then(|last| last.checked_add(1));
+        let len = self.len.min(remaining.unwrap_or(usize::MAX));
+        let (subrange, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        Some(subrange)
+    }
+}
+
+impl<Num: Integer> std::iter::FusedIterator for Subranges<Num> {}
+
+/// The numbers in part of a `ParRange`, which rayon splits between threads
+struct Steps<Num> {
+    first: Num,
+    step: u128,
+    descending: bool,
+    len: usize,
+}
+
+impl<Num: Integer> Steps<Num> {
+    /// The `i`th number, which must be within the range
+    fn nth_step(&self, i: usize) -> Num {
+        self.first.offset(i as u128 * self.step, self.descending)
+    }
+}
+
+impl<Num: Integer> Iterator for Steps<Num> {
+    type Item = Num;
+
+    fn next(&mut self) -> Option<Num> {
+        if self.len == 0 {
+            return None;
+        }
+        let n = self.first;
+        self.len -= 1;
+        if self.len > 0 {
+            self.first = self.nth_step(1);
+        }
+        Some(n)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<Num: Integer> DoubleEndedIterator for Steps<Num> {
+    fn next_back(&mut self) -> Option<Num> {
+        self.len = self.len.checked_sub(1)?;
+        Some(self.nth_step(self.len))
+    }
+}
+
+impl<Num: Integer> ExactSizeIterator for Steps<Num> {}
+
+impl<Num: Integer> Producer for Steps<Num> {
+    type Item = Num;
+    type IntoIter = Self;
+
+    fn into_iter(self) -> Self {
+        self
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let tail = Self {
+            first: if index < self.len {
+                self.nth_step(index)
+            } else {
+                self.first
+            },
+            len: self.len - index,
+            ..self
+        };
+        (Self { len: index, ..self }, tail)
+    }
+}
@@ -0,0 +1,383 @@
+//! Newline-terminated output of the classic game as contiguous blocks of bytes.
+//!
+//! The range is split into chunks whose boundaries fall at the start of a period of 15, which are
+//! rendered in parallel. Within a chunk, positive numbers are rendered one period at a time: the
+//! position of each word is fixed, and the decimal digits of every number in the period are
+//! incremented in place, using SIMD instructions where available, rather than formatted from
+//! scratch. Other numbers are formatted individually.
+//!
+//! Chunks are rendered into reusable buffers, a window of one chunk per thread at a time, while
+//! the previous window is written.
+
+use std::io::{self, Write};
+
+use rayon::prelude::*;
+
+use super::Integer;
+use crate::primitive::{self, Primitive};
+use crate::{parallel, sink, FizzBuzz};
+
+mod period;
+
+use period::{Kernel, Period};
+
+/// The count of numbers in each chunk: a whole number of periods
+const CHUNK: usize = 15 * (1 << 13);
+
+/// The shortest output for a chunk: the words take 39 bytes and the numbers at least 16 bytes in
+/// each period
+#[cfg(target_os = "linux")]
+const MIN_CHUNK_BYTES: usize = (CHUNK / 15) * 55;
+
+/// The capacity requested for a pipe which is written with `vmsplice`
+#[cfg(target_os = "linux")]
+const PIPE_SIZE: usize = 1 << 18;
+
+/// How long to wait for the reader of a pipe to read any more of the output, before leaking the
+/// buffers which are still in the pipe
+#[cfg(target_os = "linux")]
+const DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// The decimal digits of a positive number, right-aligned, followed by a newline
+struct Counter {
+    digits: [u8; primitive::MAX_DIGITS + 1],
+    start: usize,
+}
+
+impl Counter {
+    const LAST: usize = primitive::MAX_DIGITS - 1;
+
+    fn new(num: &impl Primitive) -> Self {
+        let mut digits = [b'\n'; primitive::MAX_DIGITS + 1];
+        let mut buf = [0; primitive::MAX_DIGITS];
+        let formatted = num.format(&mut buf);
+        let start = primitive::MAX_DIGITS - formatted.len();
+        digits[start..primitive::MAX_DIGITS].copy_from_slice(formatted.as_bytes());
+        Self { digits, start }
+    }
+
+    /// The digits and trailing newline
+    fn line(&self) -> &[u8] {
+        &self.digits[self.start..]
+    }
+
+    /// Adds `step`, which must be less than 10
+    fn add(&mut self, step: u8) {
+        let mut i = Self::LAST;
+        self.digits[i] += step;
+        while self.digits[i] > b'9' {
+            self.digits[i] -= 10;
+            i -= 1;
+            if i < self.start {
+                self.start = i;
+                self.digits[i] = b'0';
+            }
+            self.digits[i] += 1;
+        }
+    }
+
+    /// Renders one period, starting from a number one more than a multiple of 15, and moves on
+    /// to the next period
+    fn period(&mut self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.line());
+        self.add(1);
+        out.extend_from_slice(self.line());
+        out.extend_from_slice(b"fizz\n");
+        self.add(2);
+        out.extend_from_slice(self.line());
+        out.extend_from_slice(b"buzz\nfizz\n");
+        self.add(3);
+        out.extend_from_slice(self.line());
+        self.add(1);
+        out.extend_from_slice(self.line());
+        out.extend_from_slice(b"fizz\nbuzz\n");
+        self.add(3);
+        out.extend_from_slice(self.line());
+        out.extend_from_slice(b"fizz\n");
+        self.add(2);
+        out.extend_from_slice(self.line());
+        self.add(1);
+        out.extend_from_slice(self.line());
+        out.extend_from_slice(b"fizzbuzz\n");
+        self.add(2);
+    }
+}
+
+/// The count of numbers from `num` up to the next number which is one more than a multiple of 15
+fn to_period<Num: Integer>(num: Num) -> usize {
+    let remainder = num.magnitude() % 15;
+    let offset = if num.is_positive() {
+        16 - remainder
+    } else {
+        1 + remainder
+    };
+    (offset % 15) as usize
+}
+
+/// Renders the lines for the `len` numbers from `first` onto the end of `out`
+fn render<Num: Integer + FizzBuzz>(first: Num, len: usize, out: &mut Vec<u8>) {
+    let individual = |first: Num, len: usize, out: &mut Vec<u8>| {
+        for i in 0..len {
+            sink::write_answer_io(&first.nth(i).fizzbuzz(), out).expect("Vec<u8> is infallible");
+            out.push(b'\n');
+        }
+    };
+    let head = if first.is_positive() {
+        to_period(first)
+    } else {
+        usize::try_from(first.magnitude()).map_or(len, |magnitude| magnitude.saturating_add(1))
+    }
+    .min(len);
+    individual(first, head, out);
+    let periods = (len - head) / 15;
+    let kernel = Kernel::detect();
+    let mut next = first.nth(head).magnitude();
+    let mut remaining = periods;
+    while remaining > 0 {
+        let (mut period, run) = Period::new(next, kernel);
+        let run = usize::try_from(run).map_or(remaining, |run| run.min(remaining));
+        out.extend_from_slice(period.text());
+        for _ in 1..run {
+            period.advance();
+            out.extend_from_slice(period.text());
+        }
+        remaining -= run;
+        if remaining > 0 {
+            next += 15 * run as u128;
+        }
+    }
+    let done = head + 15 * periods;
+    if done < len {
+        individual(first.nth(done), len - done, out);
+    }
+}
+
+/// The split of a range into chunks, each beginning with a number one more than a multiple of 15,
+/// except for the first
+#[derive(Clone, Copy)]
+pub(super) struct Chunks<Num> {
+    start: Num,
+    len: usize,
+    head: usize,
+    pub(super) count: usize,
+}
+
+impl<Num: Integer + FizzBuzz> Chunks<Num> {
+    pub(super) fn new(start: Num, len: usize) -> Self {
+        let head = to_period(start);
+        let count = len.saturating_sub(head).div_ceil(CHUNK).max(1);
+        Self {
+            start,
+            len,
+            head,
+            count,
+        }
+    }
+
+    /// The index in the range of the first number in chunk `k`
+    fn bound(&self, k: usize) -> usize {
+        match k {
+            0 => 0,
+            k => k
+                .saturating_mul(CHUNK)
+                .saturating_add(self.head)
+                .min(self.len),
+        }
+    }
+
+    /// Renders chunk `k` into `out`, replacing its contents. Chunks after the last are empty.
+    pub(super) fn render(&self, k: usize, out: &mut Vec<u8>) {
+        out.clear();
+        let (first, last) = (self.bound(k), self.bound(k + 1));
+        if first < last {
+            render(self.start.nth(first), last - first, out);
+        }
+    }
+
+    /// Renders the chunks in windows of one chunk per thread, passing each window to `emit` in
+    /// order while the next is rendered.
+    ///
+    /// The buffers for a window are only rendered into again after `sets.len() - 1` later windows
+    /// have been passed to `emit`, which must be at least two. The buffers are left in `sets`, so
+    /// the caller decides when they are freed.
+    pub(super) fn windows(
+        &self,
+        sets: &mut [Vec<Vec<u8>>],
+        mut emit: impl FnMut(&[Vec<u8>]) -> io::Result<()>,
+    ) -> io::Result<()> {
+        if self.len < parallel::threshold() {
+            let buffer = sets[0].first_mut().expect("at least one buffer");
+            render(self.start, self.len, buffer);
+            return emit(&sets[0][..1]);
+        }
+        let threads = rayon::current_num_threads();
+        let windows = self.count.div_ceil(threads);
+        for set in sets.iter_mut() {
+            set.resize_with(threads, Vec::new);
+        }
+        sets[0]
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(i, buffer)| self.render(i, buffer));
+        for window in 0..windows {
+            let (ready, rest) = sets.split_first_mut().expect("at least one set");
+            let next = rest.first_mut().expect("at least two sets");
+            rayon::in_place_scope(|scope| {
+                if window + 1 < windows {
+                    for (i, buffer) in next.iter_mut().enumerate() {
+                        let k = (window + 1) * threads + i;
+                        scope.spawn(move |_| self.render(k, buffer));
+                    }
+                }
+                emit(ready)
+            })?;
+            sets.rotate_left(1);
+        }
+        Ok(())
+    }
+}
+
+/// Writes the lines for `chunks` to `out`, with one write for each chunk
+pub(super) fn write<Num: Integer + FizzBuzz>(
+    chunks: Chunks<Num>,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    chunks.windows(&mut [vec![Vec::new()], vec![Vec::new()]], |buffers| {
+        buffers.iter().try_for_each(|buffer| out.write_all(buffer))
+    })
+}
+
+/// Writes the lines for `chunks` to `out`, mapping the buffers into the pipe with `vmsplice`
+/// rather than copying them, if `out` is a blocking pipe.
+///
+/// If the pipe's capacity is more than the length of one window, it is reduced for the duration
+/// of the call and then restored, so a window has left the pipe once the following window has
+/// been spliced and its buffers can be rendered into again.
+///
+/// Before returning, waits for the reader to empty the pipe so the buffers can be freed. If the
+/// reader makes no progress for `DRAIN_TIMEOUT`, the buffers are leaked instead, so that output
+/// which is still in the pipe is never overwritten.
+#[cfg(target_os = "linux")]
+pub(super) fn splice<Num: Integer + FizzBuzz>(
+    chunks: Chunks<Num>,
+    out: &mut (impl Write + std::os::fd::AsFd),
+) -> io::Result<()> {
+    use std::os::{fd::AsRawFd, unix::fs::FileTypeExt};
+
+    const _: () = assert!(PIPE_SIZE <= MIN_CHUNK_BYTES);
+
+    let is_pipe = std::fs::File::from(out.as_fd().try_clone_to_owned()?)
+        .metadata()?
+        .file_type()
+        .is_fifo();
+    let fd = out.as_fd().as_raw_fd();
+    // SAFETY: `fcntl` with `F_GETFL` only reads the flags of the open `fd`
+    let blocking = unsafe { libc::fcntl(fd, libc::F_GETFL) } & libc::O_NONBLOCK == 0;
+    if !is_pipe || !blocking {
+        return write(chunks, out);
+    }
+    // SAFETY: `fcntl` with `F_GETPIPE_SZ` only reads the capacity of the open pipe `fd`
+    let original = unsafe { libc::fcntl(fd, libc::F_GETPIPE_SZ) };
+    if original < 0 {
+        return write(chunks, out);
+    }
+    let resize = original as usize > PIPE_SIZE;
+    if resize {
+        // SAFETY: `fcntl` with `F_SETPIPE_SZ` only changes the capacity of the open pipe `fd`,
+        // which fails without changing it if the pipe holds more than the new capacity
+        let size = unsafe { libc::fcntl(fd, libc::F_SETPIPE_SZ, PIPE_SIZE as libc::c_int) };
+        if size < 0 || size as usize > PIPE_SIZE {
+            return write(chunks, out);
+        }
+    }
+    let mut sets = [vec![Vec::new()], vec![Vec::new()], vec![Vec::new()]];
+    let written = chunks.windows(&mut sets, |buffers| {
+        buffers
+            .iter()
+            .try_for_each(|buffer| vmsplice_all(fd, buffer))
+    });
+    // Without a reader, the output left in the pipe can never be read
+    let closed = matches!(&written, Err(error) if error.kind() == io::ErrorKind::BrokenPipe);
+    if !closed && !drain(fd) {
+        core::mem::forget(sets);
+    }
+    if resize {
+        // SAFETY: as above. Restoring the original capacity cannot shrink the pipe, and is best
+        // effort: the output has already been written.
+        unsafe { libc::fcntl(fd, libc::F_SETPIPE_SZ, original) };
+    }
+    written
+}
+
+/// Maps all of `buffer` into the pipe `fd`
+#[cfg(target_os = "linux")]
+fn vmsplice_all(fd: libc::c_int, mut buffer: &[u8]) -> io::Result<()> {
+    while !buffer.is_empty() {
+        let iov = libc::iovec {
+            iov_base: buffer.as_ptr() as *mut libc::c_void,
+            iov_len: buffer.len(),
+        };
+        // SAFETY: `iov` describes initialised memory, which is not modified or freed until it has
+        // left the pipe
+        match unsafe { libc::vmsplice(fd, &iov, 1, 0) } {
+            spliced if spliced >= 0 => buffer = &buffer[spliced as usize..],
+            _ => match io::Error::last_os_error() {
+                error if error.kind() == io::ErrorKind::Interrupted => continue,
+                error => return Err(error),
+            },
+        }
+    }
+    Ok(())
+}
+
+/// Waits until the pipe `fd` is empty, returning `false` if the reader makes no progress for
+/// `DRAIN_TIMEOUT`, or `true` once it is empty or its contents cannot be queried.
+#[cfg(target_os = "linux")]
+fn drain(fd: libc::c_int) -> bool {
+    use std::time::{Duration, Instant};
+
+    let mut last = libc::c_int::MAX;
+    let mut progress = Instant::now();
+    loop {
+        let mut pending: libc::c_int = 0;
+        // SAFETY: `FIONREAD` writes the count of unread bytes into `pending`
+        if unsafe { libc::ioctl(fd, libc::FIONREAD, &mut pending) } < 0 || pending == 0 {
+            return true;
+        }
+        if pending < last {
+            last = pending;
+            progress = Instant::now();
+        } else if progress.elapsed() >= DRAIN_TIMEOUT {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn counter_carries() {
+        let mut counter = Counter::new(&97_u8);
+        let mut lines = Vec::new();
+        for step in [1, 1, 1, 3, 9, 1] {
+            lines.extend_from_slice(counter.line());
+            counter.add(step);
+        }
+        lines.extend_from_slice(counter.line());
+        assert_eq!(lines, b"97\n98\n99\n100\n103\n112\n113\n");
+    }
+
+    #[test]
+    fn chunks_are_aligned() {
+        for start in [-20_i64, -1, 0, 1, 2, 15, 16, 1_000_000] {
+            let chunks = Chunks::new(start, 5 * CHUNK);
+            for k in 1..chunks.count {
+                assert_eq!((start + chunks.bound(k) as i64).rem_euclid(15), 1);
+            }
+            assert_eq!(chunks.bound(chunks.count), 5 * CHUNK);
+        }
+    }
+}
@@ -0,0 +1,280 @@
+//! Vectorised rendering of consecutive periods of the classic game.
+//!
+//! While the numbers keep the same count of digits, the text for one period is the text for the
+//! previous period with 15 added to each number. The text is kept in a buffer alongside a mask of
+//! the positions of its digits and the amount to add at each position (5 to the units and 1 to the
+//! tens of each number). Advancing to the next period adds these amounts to every byte, then
+//! repeatedly subtracts 10 from each digit above `'9'` and carries 1 into the digit before it,
+//! until no digit is above `'9'`. Every step applies to all bytes of the text at once, so works 16
+//! or 32 bytes at a time with SSE2 (part of the x86_64 baseline) or AVX2 (if detected at runtime).
+//! Other architectures use a scalar loop with the same steps.
+
+use super::Counter;
+
+/// Bytes per vector: the buffers are padded to a multiple of this, plus one more for the carries
+/// from the following byte
+const LANES: usize = 32;
+
+/// The instructions used to advance a `Period`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Kernel {
+    /// Only used on x86_64 to validate the vectorised kernels
+    #[cfg_attr(all(target_arch = "x86_64", not(test)), allow(dead_code))]
+    Scalar,
+    #[cfg(target_arch = "x86_64")]
+    Sse2,
+    #[cfg(target_arch = "x86_64")]
+    Avx2,
+}
+
+impl Kernel {
+    /// The fastest kernel supported by this CPU
+    #[cfg(target_arch = "x86_64")]
+    pub(super) fn detect() -> Self {
+        if is_x86_feature_detected!("avx2") {
+            Kernel::Avx2
+        } else {
+            Kernel::Sse2
+        }
+    }
+
+    /// The fastest kernel supported by this CPU
+    #[cfg(not(target_arch = "x86_64"))]
+    pub(super) fn detect() -> Self {
+        Kernel::Scalar
+    }
+
+    /// Every kernel supported by this CPU
+    #[cfg(test)]
+    fn supported() -> Vec<Self> {
+        let mut kernels = vec![Kernel::Scalar];
+        #[cfg(target_arch = "x86_64")]
+        {
+            kernels.push(Kernel::Sse2);
+            if is_x86_feature_detected!("avx2") {
+                kernels.push(Kernel::Avx2);
+            }
+        }
+        kernels
+    }
+}
+
+/// The text for one period of 15 numbers, starting one more than a multiple of 15
+pub(super) struct Period {
+    len: usize,
+    text: Vec<u8>,
+    step: Vec<u8>,
+    digits: Vec<u8>,
+    carries: Vec<u8>,
+    kernel: Kernel,
+}
+
+impl Period {
+    /// The period starting from `first`, and the count of periods from it which can be rendered
+    /// by advancing it, including itself.
+    pub(super) fn new(first: u128, kernel: Kernel) -> (Self, u128) {
+        let mut text = Vec::new();
+        Counter::new(&first).period(&mut text);
+        let len = text.len();
+        let padded = len.next_multiple_of(LANES);
+        let mut step = vec![0; padded];
+        let mut digits = vec![0; padded];
+        let mut start = 0;
+        for line in text.split_inclusive(|&byte| byte == b'\n') {
+            let end = start + line.len() - 1;
+            if line[0].is_ascii_digit() {
+                digits[start..end].fill(0xFF);
+                step[end - 1] = 5;
+                if end - start > 1 {
+                    step[end - 2] = 1;
+                }
+            }
+            start += line.len();
+        }
+        text.resize(padded, 0);
+        // The numbers in every period must have as many digits as `first`
+        let last = first + 13;
+        let run = match 10_u128.checked_pow(first.ilog10() + 1) {
+            Some(limit) if last >= limit => 1,
+            Some(limit) => (limit - 1 - last) / 15 + 1,
+            None => u128::MAX,
+        };
+        let period = Self {
+            len,
+            text,
+            step,
+            digits,
+            carries: vec![0; padded + LANES],
+            kernel,
+        };
+        (period, run)
+    }
+
+    /// The text for the current period
+    pub(super) fn text(&self) -> &[u8] {
+        &self.text[..self.len]
+    }
+
+    /// Moves on to the next period, which must not need more digits
+    pub(super) fn advance(&mut self) {
+        match self.kernel {
+            Kernel::Scalar => scalar(self),
+            // SAFETY: SSE2 is part of the x86_64 baseline, and the buffers are padded to a
+            // whole number of vectors
+            #[cfg(target_arch = "x86_64")]
+            Kernel::Sse2 => unsafe { x86_64::sse2(self) },
+            // SAFETY: AVX2 was detected by `Kernel::detect()`, and the buffers are padded to a
+            // whole number of vectors
+            #[cfg(target_arch = "x86_64")]
+            Kernel::Avx2 => unsafe { x86_64::avx2(self) },
+        }
+    }
+}
+
+fn scalar(period: &mut Period) {
+    for (byte, step) in period.text.iter_mut().zip(&period.step) {
+        *byte += step;
+    }
+    loop {
+        let mut any = false;
+        for ((carry, byte), digit) in period
+            .carries
+            .iter_mut()
+            .zip(&period.text)
+            .zip(&period.digits)
+        {
+            *carry = u8::from(*digit != 0 && *byte > b'9');
+            any |= *carry != 0;
+        }
+        if !any {
+            return;
+        }
+        for (i, byte) in period.text.iter_mut().enumerate() {
+            *byte = *byte - 10 * period.carries[i] + period.carries[i + 1];
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86_64 {
+    use std::arch::x86_64::*;
+
+    use super::Period;
+
+    macro_rules! kernel {
+        ($name:ident, $feature:literal, $lanes:literal, $vector:ty, $loadu:ident, $storeu:ident,
+         $add:ident, $sub:ident, $and:ident, $set1:ident, $cmpgt:ident, $movemask:ident) => {
+            /// # Safety
+            #[doc = concat!("The CPU must support ", $feature, ".")]
+            #[target_feature(enable = $feature)]
+            pub(super) unsafe fn $name(period: &mut Period) {
+                let padded = period.text.len();
+                debug_assert_eq!(padded % $lanes, 0);
+                debug_assert!(period.carries.len() > padded);
+                let text = period.text.as_mut_ptr();
+                let step = period.step.as_ptr();
+                let digits = period.digits.as_ptr();
+                let carries = period.carries.as_mut_ptr();
+                let nine = $set1(b'9' as i8);
+                let ten = $set1(10);
+                for i in (0..padded).step_by($lanes) {
+                    let bytes = $loadu(text.add(i) as *const $vector);
+                    let bytes = $add(bytes, $loadu(step.add(i) as *const $vector));
+                    $storeu(text.add(i) as *mut $vector, bytes);
+                }
+                loop {
+                    let mut any = 0;
+                    for i in (0..padded).step_by($lanes) {
+                        let bytes = $loadu(text.add(i) as *const $vector);
+                        let mask = $loadu(digits.add(i) as *const $vector);
+                        let carry = $and($cmpgt(bytes, nine), mask);
+                        $storeu(carries.add(i) as *mut $vector, carry);
+                        any |= $movemask(carry);
+                    }
+                    if any == 0 {
+                        return;
+                    }
+                    for i in (0..padded).step_by($lanes) {
+                        let bytes = $loadu(text.add(i) as *const $vector);
+                        let carry = $loadu(carries.add(i) as *const $vector);
+                        let carry_in = $loadu(carries.add(i + 1) as *const $vector);
+                        // A carry is all ones: subtracting it adds one
+                        let bytes = $sub($sub(bytes, $and(carry, ten)), carry_in);
+                        $storeu(text.add(i) as *mut $vector, bytes);
+                    }
+                }
+            }
+        };
+    }
+
+    kernel!(
+        sse2,
+        "sse2",
+        16,
+        __m128i,
+        _mm_loadu_si128,
+        _mm_storeu_si128,
+        _mm_add_epi8,
+        _mm_sub_epi8,
+        _mm_and_si128,
+        _mm_set1_epi8,
+        _mm_cmpgt_epi8,
+        _mm_movemask_epi8
+    );
+
+    kernel!(
+        avx2,
+        "avx2",
+        32,
+        __m256i,
+        _mm256_loadu_si256,
+        _mm256_storeu_si256,
+        _mm256_add_epi8,
+        _mm256_sub_epi8,
+        _mm256_and_si256,
+        _mm256_set1_epi8,
+        _mm256_cmpgt_epi8,
+        _mm256_movemask_epi8
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// The text for the periods from `first`, rendered with `Counter`
+    fn expected(first: u128, periods: usize) -> Vec<u8> {
+        let mut counter = Counter::new(&first);
+        let mut text = Vec::new();
+        for _ in 0..periods {
+            counter.period(&mut text);
+        }
+        text
+    }
+
+    #[test]
+    fn kernels_match_counter() {
+        for kernel in Kernel::supported() {
+            for first in [16, 91, 106, 985, 99_991, 123_456_781, u64::MAX as u128 - 14] {
+                let (mut period, run) = Period::new(first, kernel);
+                let periods = run.min(1_000) as usize;
+                let mut text = period.text().to_vec();
+                for _ in 1..periods {
+                    period.advance();
+                    text.extend_from_slice(period.text());
+                }
+                assert_eq!(text, expected(first, periods), "{kernel:?} from {first}");
+            }
+        }
+    }
+
+    #[test]
+    fn runs_end_before_more_digits() {
+        assert_eq!(Period::new(1, Kernel::Scalar).1, 1);
+        assert_eq!(Period::new(16, Kernel::Scalar).1, 5);
+        assert_eq!(Period::new(91, Kernel::Scalar).1, 1);
+        assert_eq!(Period::new(106, Kernel::Scalar).1, 59);
+        let last = u128::MAX - 14;
+        assert_eq!(Period::new(last, Kernel::Scalar).1, u128::MAX);
+    }
+}
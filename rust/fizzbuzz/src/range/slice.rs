@@ -0,0 +1,97 @@
+//! Ranges described in the style of a Python `slice`.
+
+use std::fmt;
+
+use rayon::prelude::*;
+
+use super::{Integer, ParRange};
+
+/// The numbers described by a Python-style `slice(start, stop, step)`, as used by
+/// `range(start, stop, step)`: from `start`, in steps of `step`, up to but excluding `stop`.
+///
+/// - `step` defaults to `1`, and may be negative to count down. A `step` of zero is an error.
+/// - `start` and `stop` are optional. If omitted, the slice covers every value of `Num` in the
+///   direction of `step`: up from `Num::MIN` to `Num::MAX`, or down from `Num::MAX` to `Num::MIN`.
+/// - If `stop` is not beyond `start` in the direction of `step`, the slice is empty.
+///
+/// All arithmetic is exact up to the bounds of `Num`. Implements `IntoParallelIterator`, so the
+/// numbers can be evaluated with `MultiFizzBuzz`:
+///
+/// ```
+/// use fizzbuzz::range::SliceSpec;
+/// use fizzbuzz::MultiFizzBuzz;
+/// use rayon::iter::ParallelIterator;
+///
+/// let slice = SliceSpec::new(Some(15), Some(0), Some(-3)).unwrap();
+/// let answers: Vec<String> = slice.fizzbuzz().collect();
+/// assert_eq!(answers, vec!["fizzbuzz", "fizz", "fizz", "fizz", "fizz"]);
+///
+/// let slice = SliceSpec::new(Some(i8::MIN + 1), None, Some(-1)).unwrap();
+/// let answers: Vec<String> = slice.fizzbuzz().collect();
+/// assert_eq!(answers, vec!["-127", "-128"]);
+///
+/// assert!(SliceSpec::new(Some(1), Some(5), Some(0)).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SliceSpec<Num> {
+    start: Option<Num>,
+    stop: Option<Num>,
+    step: Option<Num>,
+}
+
+impl<Num: Integer> SliceSpec<Num> {
+    /// The slice `start:stop:step`, or an error if `step` is zero.
+    pub fn new(
+        start: Option<Num>,
+        stop: Option<Num>,
+        step: Option<Num>,
+    ) -> Result<Self, ZeroStepError> {
+        if step.is_some_and(|step| step.magnitude() == 0) {
+            return Err(ZeroStepError);
+        }
+        Ok(Self { start, stop, step })
+    }
+
+    /// The numbers in the slice, as a `ParRange`.
+    pub fn range(self) -> ParRange<Num> {
+        let step = self.step.map_or(1, Integer::magnitude);
+        let descending = self.step.is_some_and(|step| !step.is_positive());
+        let (start, last) = if descending {
+            let start = self.start.unwrap_or(Num::MAX);
+            let span = match self.stop {
+                Some(stop) => (start > stop).then(|| start.span(stop) - 1),
+                None => Some(start.span(Num::MIN)),
+            };
+            (start, span)
+        } else {
+            let start = self.start.unwrap_or(Num::MIN);
+            let span = match self.stop {
+                Some(stop) => (start < stop).then(|| stop.span(start) - 1),
+                None => Some(Num::MAX.span(start)),
+            };
+            (start, span)
+        };
+        ParRange::new(start, step, descending, last.map(|span| span / step))
+    }
+}
+
+impl<Num: Integer> IntoParallelIterator for SliceSpec<Num> {
+    type Iter = ParRange<Num>;
+    type Item = Num;
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.range()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A `SliceSpec` was given a step of zero.
+pub struct ZeroStepError;
+
+impl fmt::Display for ZeroStepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "slice step cannot be zero")
+    }
+}
+
+impl std::error::Error for ZeroStepError {}
@@ -0,0 +1,523 @@
+//! Configurable rule sets for playing variants of fizzbuzz.
+//!
+//! A `RuleSet` is an ordered list of `rule -> word` pairs. Any type implementing `Rule` can be
+//! used, including closures, and built-in rules are provided for:
+//! - divisibility: `Divisible`
+//! - containing a given decimal digit: `ContainsDigit`
+//! - being prime: `Prime`
+//! - being a perfect square: `PerfectSquare`
+//!
+//! When more than one rule applies the words are combined as set by `Combine`; when no rules
+//! apply the answer is set by `Fallback`.
+//!
+//! The classic game is simply the default `RuleSet`: `3 -> "fizz"`, `5 -> "buzz"`.
+//!
+//! ```
+//! use fizzbuzz::{FizzBuzz, RuleSet};
+//! use fizzbuzz::rules::ContainsDigit;
+//!
+//! let woof = RuleSet::default().divisor(7, "woof");
+//! let answer: String = 21.fizzbuzz_with(&woof).into();
+//! assert_eq!(answer, "fizzwoof");
+//!
+//! let threes = RuleSet::new()
+//!     .rule(ContainsDigit::new(3), "fizz")
+//!     .rule(|n: &i32| n % 2 == 0, "even");
+//! let answer: String = 32.fizzbuzz_with(&threes).into();
+//! assert_eq!(answer, "fizzeven");
+//! ```
+
+use alloc::{borrow::Cow, string::String, sync::Arc, vec, vec::Vec};
+use core::fmt;
+
+use crate::{
+    fastdiv::{FastDivisor, Magnitude},
+    FizzBuzzAnswer,
+};
+
+/// A test which decides whether the word for a rule should be said in place of a number.
+///
+/// Rules are paired with their word when they are added to a `RuleSet`.
+/// `Rule` is implemented for any closure `Fn(&Num) -> bool`, so custom rules can be mixed into
+/// a game alongside the built-in ones.
+///
+/// Rules must be `Send + Sync` so that a `RuleSet` can be evaluated in parallel by `MultiFizzBuzz`.
+pub trait Rule<Num>: Send + Sync {
+    /// `true` if the rule applies to `num`.
+    fn applies(&self, num: &Num) -> bool;
+
+    /// The period of the rule, if it applies to exactly the multiples of a single number.
+    ///
+    /// Allows `range::RangeFizzBuzz` to evaluate ranges without testing every number.
+    fn period(&self) -> Option<&Num> {
+        None
+    }
+}
+
+impl<Num, F> Rule<Num> for F
+where
+    F: Fn(&Num) -> bool + Send + Sync,
+{
+    fn applies(&self, num: &Num) -> bool {
+        self(num)
+    }
+}
+
+/// A type which can be the divisor of a `Divisible` rule.
+///
+/// Implemented for the primitive integers, whose divisibility is tested with a precomputed
+/// multiplicative inverse of the divisor rather than `%`, and for `f32` and `f64`. The inverse
+/// tests the magnitudes of the number and divisor, so never overflows, even for `i32::MIN` and
+/// `-1`. Any other type can implement it with an empty `impl`, and is tested with `%`:
+///
+/// ```
+/// use fizzbuzz::rules::Divisor;
+///
+/// struct Tally(u32);
+///
+/// impl Divisor for Tally {}
+/// ```
+pub trait Divisor {
+    /// The magnitude of a primitive integer, which selects the precomputed test
+    #[doc(hidden)]
+    fn magnitude(&self) -> Option<Magnitude> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// Applies to any number which is directly divisible by the divisor.
+///
+/// Available for any type `<Num>` which supports the same operations as `FizzBuzz::fizzbuzz()`
+/// and implements `Divisor`. For primitive integers, the divisibility test uses a precomputed
+/// multiplicative inverse of the divisor rather than `%`.
+pub struct Divisible<Num> {
+    divisor: Num,
+    zero: Option<Num>,
+    fast: Option<FastDivisor>,
+}
+
+impl<Num> Divisible<Num>
+where
+    Num: TryFrom<u8> + PartialEq + Divisor,
+{
+    /// # Panics
+    /// If `divisor` is zero.
+    pub fn new(divisor: Num) -> Self {
+        let zero = <Num>::try_from(0_u8).ok();
+        assert!(zero.as_ref() != Some(&divisor), "divisor cannot be zero");
+        Self {
+            fast: divisor.magnitude().and_then(FastDivisor::new),
+            divisor,
+            zero,
+        }
+    }
+
+    /// The divisor for this rule.
+    pub fn divisor(&self) -> &Num {
+        &self.divisor
+    }
+}
+
+impl<Num> Rule<Num> for Divisible<Num>
+where
+    Num: PartialEq + Clone + Send + Sync + Divisor,
+    for<'a> &'a Num: core::ops::Rem<Num, Output = Num>,
+{
+    fn applies(&self, num: &Num) -> bool {
+        match (&self.fast, num.magnitude(), &self.zero) {
+            (Some(fast), Some(num), _) => fast.divides(num),
+            (_, _, Some(zero)) => &(num % self.divisor.clone()) == zero,
+            _ => false,
+        }
+    }
+
+    fn period(&self) -> Option<&Num> {
+        self.zero.as_ref().map(|_| &self.divisor)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Applies to any number whose decimal representation (via `Display`) contains the digit.
+pub struct ContainsDigit(char);
+
+impl ContainsDigit {
+    /// # Panics
+    /// If `digit` is not a single decimal digit (`0..=9`).
+    pub fn new(digit: u8) -> Self {
+        assert!(digit < 10, "{digit} is not a single decimal digit");
+        Self(char::from(b'0' + digit))
+    }
+}
+
+/// Scans formatted output for a character without allocating a `String`.
+struct FindChar {
+    target: char,
+    found: bool,
+}
+
+impl fmt::Write for FindChar {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if s.contains(self.target) {
+            self.found = true;
+            return Err(fmt::Error); // Stop formatting, no need to see any more
+        }
+        Ok(())
+    }
+}
+
+impl<Num> Rule<Num> for ContainsDigit
+where
+    Num: fmt::Display,
+{
+    fn applies(&self, num: &Num) -> bool {
+        let mut finder = FindChar {
+            target: self.0,
+            found: false,
+        };
+        let _ = fmt::write(&mut finder, format_args!("{num}"));
+        finder.found
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// Applies to any prime number. Available for the primitive integer types.
+pub struct Prime;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// Applies to any perfect square (including `0`). Available for the primitive integer types.
+pub struct PerfectSquare;
+
+macro_rules! impl_prime {
+    ($($t:ty),*) => {
+        $(
+            impl Rule<$t> for Prime {
+                fn applies(&self, num: &$t) -> bool {
+                    let num = *num;
+                    if num < 2 {
+                        return false;
+                    }
+                    if num < 4 {
+                        return true;
+                    }
+                    if num % 2 == 0 || num % 3 == 0 {
+                        return false;
+                    }
+                    let mut i: $t = 5;
+                    while i <= num / i {
+                        if num % i == 0 || num % (i + 2) == 0 {
+                            return false;
+                        }
+                        i += 6;
+                    }
+                    true
+                }
+            }
+        )*
+    };
+}
+
+impl_prime!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+macro_rules! impl_perfect_square {
+    (unsigned: $($u:ty),*; signed: $($i:ty),*) => {
+        $(
+            impl Rule<$u> for PerfectSquare {
+                fn applies(&self, num: &$u) -> bool {
+                    let root = num.isqrt();
+                    root * root == *num
+                }
+            }
+        )*
+        $(
+            impl Rule<$i> for PerfectSquare {
+                fn applies(&self, num: &$i) -> bool {
+                    match num.checked_isqrt() {
+                        Some(root) => root * root == *num,
+                        None => false, // negative
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_perfect_square!(
+    unsigned: u8, u16, u32, u64, u128, usize;
+    signed: i8, i16, i32, i64, i128, isize
+);
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+/// How the words are combined when more than one rule applies to a number.
+pub enum Combine {
+    /// Concatenate the words of every rule which applies, in order. E.g. `"fizzbuzz"`
+    #[default]
+    Concat,
+    /// Join the words of every rule which applies, in order, with a separator. E.g. `"Fizz-Buzz"`
+    Join(Cow<'static, str>),
+    /// Only say the word for the first rule which applies.
+    FirstMatch,
+    /// Only say the word for the rule with the highest priority which applies.
+    /// The earliest rule wins if more than one has the same priority.
+    HighestPriority,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+/// What to say when no rules apply to a number.
+pub enum Fallback {
+    /// Say the number, as a `FizzBuzzAnswer::Number`
+    #[default]
+    Number,
+    /// Say a fixed word, as a `FizzBuzzAnswer::Word`
+    Word(Cow<'static, str>),
+}
+
+/// A rule, the word to say when it applies, and its priority
+struct Entry<Num> {
+    rule: Arc<dyn Rule<Num>>,
+    word: Cow<'static, str>,
+    priority: i32,
+}
+
+impl<Num> Clone for Entry<Num> {
+    fn clone(&self) -> Self {
+        Self {
+            rule: self.rule.clone(),
+            word: self.word.clone(),
+            priority: self.priority,
+        }
+    }
+}
+
+/// An ordered set of `rule -> word` pairs, with a strategy for combining the words when more
+/// than one rule applies and a fallback for when none do.
+///
+/// By default the words are concatenated in the order in which the rules were added
+/// (`Combine::Concat`) and numbers to which no rules apply are returned as
+/// `FizzBuzzAnswer::Number` (`Fallback::Number`).
+///
+/// The answers "fizz", "buzz" and "fizzbuzz" are given as `FizzBuzzAnswer::Fizz`, `Buzz` and
+/// `Fizzbuzz`, so `RuleSet::default()` gives exactly the same answers as `FizzBuzz::fizzbuzz()`.
+/// Any other words are given as a `FizzBuzzAnswer::Word`.
+///
+/// ```
+/// use fizzbuzz::{FizzBuzz, RuleSet};
+/// use fizzbuzz::rules::{Combine, Divisible, Fallback};
+///
+/// let rules = RuleSet::new()
+///     .divisor(3, "Fizz")
+///     .rule_with_priority(Divisible::new(5), "Buzz", 1)
+///     .combine(Combine::Join("-".into()))
+///     .fallback(Fallback::Word("...".into()));
+/// let answers: Vec<String> = [1, 3, 15].iter().map(|n| n.fizzbuzz_with(&rules).into()).collect();
+/// assert_eq!(answers, vec!["...", "Fizz", "Fizz-Buzz"]);
+///
+/// let rules = rules.combine(Combine::HighestPriority);
+/// let answer: String = 15.fizzbuzz_with(&rules).into();
+/// assert_eq!(answer, "Buzz");
+/// ```
+pub struct RuleSet<Num> {
+    rules: Vec<Entry<Num>>,
+    combine: Combine,
+    fallback: Fallback,
+}
+
+impl<Num> RuleSet<Num> {
+    /// Creates an empty `RuleSet`, which will answer every number with itself.
+    pub fn new() -> Self {
+        Self {
+            rules: vec![],
+            combine: Combine::default(),
+            fallback: Fallback::default(),
+        }
+    }
+
+    /// Adds a rule to say `word` for any number to which `rule` applies, with priority `0`.
+    pub fn rule(self, rule: impl Rule<Num> + 'static, word: impl Into<Cow<'static, str>>) -> Self {
+        self.rule_with_priority(rule, word, 0)
+    }
+
+    /// Adds a rule to say `word` for any number to which `rule` applies, with a given `priority`
+    /// for use with `Combine::HighestPriority`.
+    pub fn rule_with_priority(
+        mut self,
+        rule: impl Rule<Num> + 'static,
+        word: impl Into<Cow<'static, str>>,
+        priority: i32,
+    ) -> Self {
+        self.rules.push(Entry {
+            rule: Arc::new(rule),
+            word: word.into(),
+            priority,
+        });
+        self
+    }
+
+    /// Sets how words are combined when more than one rule applies.
+    pub fn combine(mut self, combine: Combine) -> Self {
+        self.combine = combine;
+        self
+    }
+
+    /// Sets what to say when no rules apply.
+    pub fn fallback(mut self, fallback: Fallback) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    /// Iterates over the words in the order in which their rules will be evaluated.
+    pub fn words(&self) -> impl Iterator<Item = &str> {
+        self.rules.iter().map(|entry| entry.word.as_ref())
+    }
+
+    /// How words are combined when more than one rule applies.
+    pub fn combination(&self) -> &Combine {
+        &self.combine
+    }
+
+    /// What to say when no rules apply.
+    pub fn fallback_answer(&self) -> &Fallback {
+        &self.fallback
+    }
+
+    /// The number of rules in the set.
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// `true` if the set contains no rules.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+impl<Num> RuleSet<Num>
+where
+    Num: TryFrom<u8> + PartialEq + Clone + Send + Sync + Divisor + 'static,
+    for<'a> &'a Num: core::ops::Rem<Num, Output = Num>,
+{
+    /// Adds a rule to say `word` for any number which is directly divisible by `divisor`.
+    ///
+    /// # Panics
+    /// If `divisor` is zero.
+    pub fn divisor(self, divisor: Num, word: impl Into<Cow<'static, str>>) -> Self {
+        self.rule(Divisible::new(divisor), word)
+    }
+}
+
+/// The classic game: `3 -> "fizz"`, `5 -> "buzz"`.
+///
+/// Divisors which cannot be represented by `Num` are skipped, matching the behaviour of
+/// `FizzBuzz::fizzbuzz()`.
+impl<Num> Default for RuleSet<Num>
+where
+    Num: TryFrom<u8> + PartialEq + Clone + Send + Sync + Divisor + 'static,
+    for<'a> &'a Num: core::ops::Rem<Num, Output = Num>,
+{
+    fn default() -> Self {
+        let mut rules = Self::new();
+        for (divisor, word) in [(3_u8, "fizz"), (5_u8, "buzz")] {
+            if let Ok(divisor) = <Num>::try_from(divisor) {
+                rules = rules.divisor(divisor, word);
+            }
+        }
+        rules
+    }
+}
+
+impl<Num> Clone for RuleSet<Num> {
+    fn clone(&self) -> Self {
+        Self {
+            rules: self.rules.clone(),
+            combine: self.combine.clone(),
+            fallback: self.fallback.clone(),
+        }
+    }
+}
+
+impl<Num> fmt::Debug for RuleSet<Num> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RuleSet")
+            .field("words", &self.words().collect::<Vec<_>>())
+            .field("combine", &self.combine)
+            .field("fallback", &self.fallback)
+            .finish()
+    }
+}
+
+impl<Num> RuleSet<Num>
+where
+    Num: Clone,
+{
+    /// The period of each rule, or `None` if any of the rules is not periodic.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn periods(&self) -> Option<Vec<&Num>> {
+        self.rules.iter().map(|entry| entry.rule.period()).collect()
+    }
+
+    /// Evaluates the rules for `num`.
+    pub(crate) fn answer(&self, num: &Num) -> FizzBuzzAnswer<Num> {
+        self.answer_from(
+            num,
+            self.rules.iter().filter(|entry| entry.rule.applies(num)),
+        )
+    }
+
+    /// The answer for `num` given the indices, in order, of the rules which apply to it.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn answer_for_rules(
+        &self,
+        num: &Num,
+        applies: impl Iterator<Item = usize>,
+    ) -> FizzBuzzAnswer<Num> {
+        self.answer_from(num, applies.map(|index| &self.rules[index]))
+    }
+
+    /// Combines the words for the rules which apply, or falls back if there are none.
+    fn answer_from<'a>(
+        &'a self,
+        num: &Num,
+        mut applies: impl Iterator<Item = &'a Entry<Num>>,
+    ) -> FizzBuzzAnswer<Num> {
+        let word = match &self.combine {
+            Combine::Concat => join(applies.map(|entry| &entry.word), ""),
+            Combine::Join(separator) => join(applies.map(|entry| &entry.word), separator),
+            Combine::FirstMatch => applies.next().map(|entry| entry.word.clone()),
+            Combine::HighestPriority => applies
+                .reduce(|best, entry| {
+                    if entry.priority > best.priority {
+                        entry
+                    } else {
+                        best
+                    }
+                })
+                .map(|entry| entry.word.clone()),
+        };
+        match (word, &self.fallback) {
+            (Some(word), _) => FizzBuzzAnswer::from_word(word.into()),
+            (None, Fallback::Number) => FizzBuzzAnswer::Number(num.clone()),
+            (None, Fallback::Word(word)) => FizzBuzzAnswer::from_word(word.clone().into()),
+        }
+    }
+}
+
+/// Joins the words with `separator`, only allocating if there is more than one word
+fn join<'a>(
+    mut words: impl Iterator<Item = &'a Cow<'static, str>>,
+    separator: &str,
+) -> Option<Cow<'static, str>> {
+    match (words.next(), words.next()) {
+        (None, _) => None,
+        (Some(word), None) => Some(word.clone()),
+        (Some(first), Some(second)) => {
+            let mut joined = String::from(first.as_ref());
+            joined.push_str(separator);
+            joined.push_str(second);
+            words.for_each(|word| {
+                joined.push_str(separator);
+                joined.push_str(word)
+            });
+            Some(joined.into())
+        }
+    }
+}
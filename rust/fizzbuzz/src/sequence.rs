@@ -0,0 +1,193 @@
+//! The fizzbuzz sequence from any number onwards, without building a range.
+//!
+//! The answers of the classic game repeat every 15 numbers, so a `Sequence` only tracks each
+//! number's remainder modulo 15 alongside the number itself. Moving `k` numbers ahead is a single
+//! multiplication, so `nth()` and `skip()` take the same time however far they jump, and a
+//! sequence can be split into bounded chunks which are evaluated independently:
+//!
+//! ```
+//! use fizzbuzz::{FizzBuzzAnswer, Sequence};
+//!
+//! let mut odd = Sequence::with_step(1_u64, 2);
+//! assert_eq!(odd.next(), Some((1, FizzBuzzAnswer::Number(1))));
+//! assert_eq!(odd.nth(6), Some((15, FizzBuzzAnswer::Fizzbuzz)));
+//!
+//! let mut chunks = Sequence::new(1_u64).chunks(1_000_000);
+//! let second = chunks.nth(1).unwrap();
+//! assert_eq!(second.last(), Some((2_000_000, FizzBuzzAnswer::Buzz)));
+//! ```
+//!
+//! A sequence ends if the next number would be outside the bounds of its type. Until then it
+//! reports its exact length, or a `size_hint()` of `(usize::MAX, None)` if it is too long to
+//! count, or never ends because its step is zero.
+
+use core::iter::{FusedIterator, Take};
+
+pub use crate::integer::Integer;
+use crate::FizzBuzzAnswer;
+
+/// The period of the classic game
+const PERIOD: u8 = 15;
+
+/// `n + k * step`, or `None` if it is outside the bounds of the type
+fn checked_nth<Num: Integer>(n: Num, step: Num, k: u128) -> Option<Num> {
+    let distance = step.magnitude().checked_mul(k)?;
+    let descending = !step.is_positive();
+    let room = if descending {
+        n.span(Num::MIN)
+    } else {
+        Num::MAX.span(n)
+    };
+    (distance <= room).then(|| n.offset(distance, descending))
+}
+
+/// The remainder after dividing `n` by 15, which is never negative
+fn residue<Num: Integer>(n: Num) -> u8 {
+    let remainder = (n.magnitude() % u128::from(PERIOD)) as u8;
+    if n.is_positive() {
+        remainder
+    } else {
+        (PERIOD - remainder) % PERIOD
+    }
+}
+
+/// An infinite, lazy iterator over `(n, n.fizzbuzz())` from any starting number, in steps of
+/// any size.
+///
+/// Cloning a sequence is cheap, and the clone continues from the same point independently.
+/// Descending sequences use a negative step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sequence<Num> {
+    /// `None` once the sequence has left the bounds of `Num`
+    next: Option<Num>,
+    step: Num,
+    residue: u8,
+    step_residue: u8,
+}
+
+impl<Num: Integer> Sequence<Num> {
+    /// The sequence `start, start + 1, start + 2, ...`
+    pub fn new(start: Num) -> Self {
+        Self::with_step(start, Num::ONE)
+    }
+
+    /// The sequence `start, start + step, start + 2 * step, ...`
+    pub fn with_step(start: Num, step: Num) -> Self {
+        Self {
+            next: Some(start),
+            step,
+            residue: residue(start),
+            step_residue: residue(step),
+        }
+    }
+
+    /// The next `len` answers, and the sequence which follows them.
+    pub fn split_at(self, len: usize) -> (Take<Self>, Self) {
+        let mut rest = self.clone();
+        rest.advance(len as u128);
+        (self.take(len), rest)
+    }
+
+    /// Splits the sequence into consecutive chunks of `len` answers, which can be evaluated
+    /// independently, for example on different threads.
+    ///
+    /// # Panics
+    /// If `len` is zero.
+    pub fn chunks(self, len: usize) -> Chunks<Num> {
+        assert!(len > 0, "chunks cannot be empty");
+        Chunks { rest: self, len }
+    }
+
+    /// The count of numbers left before the sequence leaves the bounds of `Num`, or `None` if
+    /// there is no end or the count does not fit into a `usize`
+    fn remaining(&self) -> Option<usize> {
+        let Some(next) = self.next else {
+            return Some(0);
+        };
+        let step = self.step.magnitude();
+        if step == 0 {
+            return None;
+        }
+        let room = if self.step.is_positive() {
+            Num::MAX.span(next)
+        } else {
+            next.span(Num::MIN)
+        };
+        usize::try_from(room / step).ok()?.checked_add(1)
+    }
+
+    /// Moves `k` numbers ahead, without checking whether the sequence has ended
+    fn advance(&mut self, k: u128) {
+        self.next = self.next.and_then(|next| checked_nth(next, self.step, k));
+        let k = (k % u128::from(PERIOD)) as u8;
+        self.residue = (self.residue + k * self.step_residue) % PERIOD;
+    }
+}
+
+impl<Num: Integer> Iterator for Sequence<Num> {
+    type Item = (Num, FizzBuzzAnswer<Num>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = self.next?;
+        let answer = match self.residue {
+            0 => FizzBuzzAnswer::Fizzbuzz,
+            3 | 6 | 9 | 12 => FizzBuzzAnswer::Fizz,
+            5 | 10 => FizzBuzzAnswer::Buzz,
+            _ => FizzBuzzAnswer::Number(n),
+        };
+        self.advance(1);
+        Some((n, answer))
+    }
+
+    fn nth(&mut self, k: usize) -> Option<Self::Item> {
+        self.advance(k as u128);
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining() {
+            Some(remaining) => (remaining, Some(remaining)),
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+impl<Num: Integer> FusedIterator for Sequence<Num> {}
+
+/// Consecutive, bounded chunks of a `Sequence`, created by `Sequence::chunks()`.
+///
+/// Skipping chunks with `nth()` or `skip()` takes the same time however many are skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunks<Num> {
+    rest: Sequence<Num>,
+    len: usize,
+}
+
+impl<Num: Integer> Iterator for Chunks<Num> {
+    type Item = Take<Sequence<Num>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rest.next?;
+        let chunk = self.rest.clone().take(self.len);
+        self.rest.advance(self.len as u128);
+        Some(chunk)
+    }
+
+    fn nth(&mut self, k: usize) -> Option<Self::Item> {
+        // Cannot overflow: both factors are less than 2^64
+        self.rest.advance(k as u128 * self.len as u128);
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.rest.remaining() {
+            Some(remaining) => {
+                let chunks = remaining.div_ceil(self.len);
+                (chunks, Some(chunks))
+            }
+            None => (usize::MAX, None),
+        }
+    }
+}
+
+impl<Num: Integer> FusedIterator for Chunks<Num> {}
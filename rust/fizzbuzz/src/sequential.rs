@@ -0,0 +1,196 @@
+//! Lazy, sequential answers for any `Iterator`, without `rayon` or a thread pool.
+
+use core::fmt::{self, Display};
+#[cfg(feature = "std")]
+use std::io;
+
+#[cfg(feature = "alloc")]
+use crate::RuleSet;
+use crate::{game::StaticRules, sink, stateful::Monoid, FizzBuzz, FizzBuzzAnswer, StatefulRule};
+
+/// The length of text collected before it is written to an `io::Write` sink
+#[cfg(feature = "std")]
+const BUFFER_LEN: usize = 1 << 16;
+
+/// Used to obtain the answers for the items of any `Iterator` lazily, one at a time, on the
+/// current thread.
+///
+/// This is the sequential counterpart of `MultiFizzBuzz`: it needs no thread pool and is
+/// available without the `rayon` feature. The methods have distinct names, so both traits can be
+/// imported together, including for types such as `Range` which are both an `Iterator` and a
+/// rayon `IntoParallelIterator`.
+///
+/// ```
+/// use fizzbuzz::{FizzBuzzAnswer, SequentialFizzBuzz};
+///
+/// let mut answers = (1..).fizzbuzz_seq::<FizzBuzzAnswer<u32>>().skip(14);
+/// assert_eq!(answers.next(), Some(FizzBuzzAnswer::Fizzbuzz));
+///
+/// # #[cfg(feature = "rayon")] {
+/// use fizzbuzz::MultiFizzBuzz;
+/// use rayon::iter::ParallelIterator;
+///
+/// let sequential: Vec<String> = (1..16).fizzbuzz_seq().collect();
+/// let parallel: Vec<String> = (1..16).fizzbuzz().collect();
+/// assert_eq!(sequential, parallel);
+/// # }
+/// ```
+pub trait SequentialFizzBuzz {
+    /// The type of the numbers being fizzbuzzed
+    type Num;
+
+    /// Returns an iterator which lazily provides the answers for the items of the implementing
+    /// iterator, converted to a requested type (e.g. `FizzBuzzAnswer`, `String`, `Cow<str>`).
+    fn fizzbuzz_seq<Rtn>(self) -> impl Iterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Self::Num>>;
+
+    /// As `fizzbuzz_seq()`, when playing with a custom `RuleSet`.
+    #[cfg(feature = "alloc")]
+    fn fizzbuzz_seq_with<Rtn>(self, rules: &RuleSet<Self::Num>) -> impl Iterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Self::Num>>;
+
+    /// As `fizzbuzz_seq()`, using a rule set which is fixed at compile time, such as
+    /// `game::Game<3, 7>`.
+    fn fizzbuzz_seq_as<Rules, Rtn>(self) -> impl Iterator<Item = Rtn>
+    where
+        Rules: StaticRules,
+        Rtn: From<FizzBuzzAnswer<Self::Num>>;
+
+    /// As `fizzbuzz_seq()`, after applying a `StatefulRule`, whose answers depend on the answers for
+    /// earlier items. The state is combined as each item is evaluated.
+    fn fizzbuzz_seq_scan<Rtn, Rule>(self, rule: &Rule) -> impl Iterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Self::Num>>,
+        Rule: StatefulRule<Self::Num>;
+
+    /// As `fizzbuzz_seq_scan()` but using a custom `RuleSet` to provide the stateless answers.
+    #[cfg(feature = "alloc")]
+    fn fizzbuzz_seq_with_scan<Rtn, Rule>(
+        self,
+        rules: &RuleSet<Self::Num>,
+        rule: &Rule,
+    ) -> impl Iterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Self::Num>>,
+        Rule: StatefulRule<Self::Num>;
+
+    /// Writes the answers for the items of the implementing iterator into an `io::Write` sink,
+    /// such as a file or socket, with `separator` between each answer.
+    ///
+    /// The answers are collected in a reusable buffer and written in large blocks. Nothing is
+    /// written after the final answer, and the sink is not flushed.
+    #[cfg(feature = "std")]
+    fn write_fizzbuzz_seq(self, out: &mut impl io::Write, separator: &str) -> io::Result<()>
+    where
+        Self::Num: Display;
+
+    /// As `write_fizzbuzz_seq()` but for a `fmt::Write` sink, such as a `String`.
+    fn write_fizzbuzz_seq_fmt(self, out: &mut impl fmt::Write, separator: &str) -> fmt::Result
+    where
+        Self::Num: Display;
+}
+
+impl<Iter, Num> SequentialFizzBuzz for Iter
+where
+    Iter: Iterator<Item = Num>,
+    Num: FizzBuzz,
+{
+    type Num = Num;
+
+    fn fizzbuzz_seq<Rtn>(self) -> impl Iterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Num>>,
+    {
+        self.map(|n| n.fizzbuzz().into())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn fizzbuzz_seq_with<Rtn>(self, rules: &RuleSet<Num>) -> impl Iterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Num>>,
+    {
+        self.map(|n| n.fizzbuzz_with(rules).into())
+    }
+
+    fn fizzbuzz_seq_as<Rules, Rtn>(self) -> impl Iterator<Item = Rtn>
+    where
+        Rules: StaticRules,
+        Rtn: From<FizzBuzzAnswer<Num>>,
+    {
+        self.map(|n| n.fizzbuzz_as::<Rules>().into())
+    }
+
+    fn fizzbuzz_seq_scan<Rtn, Rule>(self, rule: &Rule) -> impl Iterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Num>>,
+        Rule: StatefulRule<Num>,
+    {
+        scan(self.map(|n| (n.fizzbuzz(), n)), rule)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn fizzbuzz_seq_with_scan<Rtn, Rule>(
+        self,
+        rules: &RuleSet<Num>,
+        rule: &Rule,
+    ) -> impl Iterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Num>>,
+        Rule: StatefulRule<Num>,
+    {
+        scan(self.map(|n| (n.fizzbuzz_with(rules), n)), rule)
+    }
+
+    #[cfg(feature = "std")]
+    fn write_fizzbuzz_seq(self, out: &mut impl io::Write, separator: &str) -> io::Result<()>
+    where
+        Num: Display,
+    {
+        let mut buffer = String::with_capacity(BUFFER_LEN + separator.len());
+        for (i, n) in self.enumerate() {
+            if i > 0 {
+                buffer.push_str(separator);
+            }
+            sink::write_answer(&n.fizzbuzz(), &mut buffer)
+                .expect("writing to a String cannot fail");
+            if buffer.len() >= BUFFER_LEN {
+                out.write_all(buffer.as_bytes())?;
+                buffer.clear();
+            }
+        }
+        out.write_all(buffer.as_bytes())
+    }
+
+    fn write_fizzbuzz_seq_fmt(self, out: &mut impl fmt::Write, separator: &str) -> fmt::Result
+    where
+        Num: Display,
+    {
+        for (i, n) in self.enumerate() {
+            if i > 0 {
+                out.write_str(separator)?;
+            }
+            sink::write_answer(&n.fizzbuzz(), out)?;
+        }
+        Ok(())
+    }
+}
+
+/// Applies a stateful `rule` to the stateless answers, combining the state from left to right.
+fn scan<'r, Answers, Num, Rule, Rtn>(
+    answers: Answers,
+    rule: &'r Rule,
+) -> impl Iterator<Item = Rtn> + use<'r, Answers, Num, Rule, Rtn>
+where
+    Answers: Iterator<Item = (FizzBuzzAnswer<Num>, Num)>,
+    Rule: StatefulRule<Num>,
+    Rtn: From<FizzBuzzAnswer<Num>>,
+{
+    answers.scan(Rule::State::empty(), move |state, (answer, num)| {
+        let observed = rule.observe(&num, &answer);
+        let answer = rule.answer(state, &num, answer);
+        *state = state.combine(&observed);
+        Some(answer.into())
+    })
+}
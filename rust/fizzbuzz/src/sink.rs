@@ -0,0 +1,216 @@
+//! Writing answers directly into `fmt::Write` and `io::Write` sinks, or fixed-size buffers.
+//!
+//! Answers are rendered into a set of text buffers, one for each thread, a window of numbers at a
+//! time. Each window is split between the buffers in order, rendered in parallel and then passed
+//! to the sink before the buffers are cleared and reused for the next window. Memory use is
+//! bounded by the size of a window, however many numbers are written.
+
+use core::fmt::{self, Display, Write};
+#[cfg(feature = "std")]
+use std::io;
+
+#[cfg(feature = "rayon")]
+use rayon::{
+    iter::{
+        plumbing::{Producer, ProducerCallback},
+        IndexedParallelIterator,
+    },
+    ThreadPool,
+};
+
+#[cfg(feature = "rayon")]
+use crate::parallel;
+use crate::FizzBuzzAnswer;
+
+/// The count of numbers rendered into each buffer per window
+#[cfg(feature = "rayon")]
+const NUMS_PER_BUFFER: usize = 1 << 14;
+
+/// Writes `answer` into `out`
+pub(crate) fn write_answer<Num: Display>(
+    answer: &FizzBuzzAnswer<Num>,
+    out: &mut impl Write,
+) -> fmt::Result {
+    match answer {
+        FizzBuzzAnswer::Number(n) => write!(out, "{n}"),
+        answer => out.write_str(answer.word().expect("only numbers have no word")),
+    }
+}
+
+/// Writes `answer` into the start of `buf`, returning the text written
+pub(crate) fn write_answer_buf<'buf, Num: Display>(
+    answer: &FizzBuzzAnswer<Num>,
+    buf: &'buf mut [u8],
+) -> Result<&'buf str, fmt::Error> {
+    /// A `fmt::Write` into a fixed buffer, which fails rather than truncating text
+    struct Slice<'buf> {
+        buf: &'buf mut [u8],
+        len: usize,
+    }
+
+    impl Write for Slice<'_> {
+        fn write_str(&mut self, text: &str) -> fmt::Result {
+            let end = self.len + text.len();
+            self.buf
+                .get_mut(self.len..end)
+                .ok_or(fmt::Error)?
+                .copy_from_slice(text.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    let mut slice = Slice { buf, len: 0 };
+    write_answer(answer, &mut slice)?;
+    let Slice { buf, len } = slice;
+    Ok(core::str::from_utf8(&buf[..len]).expect("only whole strs are written"))
+}
+
+/// Writes `answer` into `out`
+#[cfg(feature = "std")]
+pub(crate) fn write_answer_io<Num: Display>(
+    answer: &FizzBuzzAnswer<Num>,
+    out: &mut impl io::Write,
+) -> io::Result<()> {
+    match answer {
+        FizzBuzzAnswer::Number(n) => write!(out, "{n}"),
+        answer => out.write_all(answer.word().expect("only numbers have no word").as_bytes()),
+    }
+}
+
+/// Renders the answers for `par_iter` on `pool`, each followed by `separator`, passing the rendered
+/// text to `emit` in order. The final separator is not emitted.
+#[cfg(feature = "rayon")]
+pub(crate) fn write_all<Iter, Answer, Emit, E>(
+    par_iter: Iter,
+    pool: Option<&ThreadPool>,
+    separator: &str,
+    answer: Answer,
+    emit: Emit,
+) -> Result<(), E>
+where
+    Iter: IndexedParallelIterator,
+    Iter::Item: Display,
+    Answer: Fn(&Iter::Item) -> FizzBuzzAnswer<Iter::Item> + Sync,
+    Emit: FnMut(&str) -> Result<(), E>,
+{
+    let len = par_iter.len();
+    let buffers = if len < parallel::threshold() {
+        1 //Don't parallelise when small
+    } else {
+        parallel::num_threads(pool)
+    };
+    par_iter.with_producer(Windows {
+        len,
+        pool,
+        buffers: vec![String::new(); buffers],
+        separator,
+        answer,
+        emit,
+    })
+}
+
+#[cfg(feature = "rayon")]
+struct Windows<'a, Answer, Emit> {
+    len: usize,
+    pool: Option<&'a ThreadPool>,
+    buffers: Vec<String>,
+    separator: &'a str,
+    answer: Answer,
+    emit: Emit,
+}
+
+#[cfg(feature = "rayon")]
+impl<Num, Answer, Emit, E> ProducerCallback<Num> for Windows<'_, Answer, Emit>
+where
+    Num: Display + Send,
+    Answer: Fn(&Num) -> FizzBuzzAnswer<Num> + Sync,
+    Emit: FnMut(&str) -> Result<(), E>,
+{
+    type Output = Result<(), E>;
+
+    fn callback<P: Producer<Item = Num>>(mut self, producer: P) -> Result<(), E> {
+        let window = NUMS_PER_BUFFER * self.buffers.len();
+        let mut rest = producer;
+        let mut remaining = self.len;
+        while remaining > 0 {
+            let len = remaining.min(window);
+            let (nums, tail) = rest.split_at(len);
+            let (buffers, separator, answer) = (&mut self.buffers, self.separator, &self.answer);
+            parallel::install(self.pool, || render(nums, len, buffers, separator, answer));
+            rest = tail;
+            remaining -= len;
+            let last = if remaining == 0 {
+                self.buffers.iter().rposition(|buffer| !buffer.is_empty())
+            } else {
+                None
+            };
+            for (i, buffer) in self.buffers.iter().enumerate() {
+                match last {
+                    Some(last) if i == last => {
+                        (self.emit)(&buffer[..buffer.len() - self.separator.len()])?
+                    }
+                    _ => (self.emit)(buffer)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Renders `nums` into `buffers`, splitting them as evenly as possible
+#[cfg(feature = "rayon")]
+fn render<P, Answer>(nums: P, len: usize, buffers: &mut [String], separator: &str, answer: &Answer)
+where
+    P: Producer,
+    P::Item: Display,
+    Answer: Fn(&P::Item) -> FizzBuzzAnswer<P::Item> + Sync,
+{
+    match buffers {
+        [buffer] => {
+            buffer.clear();
+            for n in nums.into_iter() {
+                write_answer(&answer(&n), buffer).expect("writing to a String cannot fail");
+                buffer.push_str(separator);
+            }
+        }
+        _ => {
+            let half = buffers.len() / 2;
+            let mid = len * half / buffers.len();
+            let (left, right) = nums.split_at(mid);
+            let (left_buffers, right_buffers) = buffers.split_at_mut(half);
+            rayon::join(
+                || render(left, mid, left_buffers, separator, answer),
+                || render(right, len - mid, right_buffers, separator, answer),
+            );
+        }
+    }
+}
+
+#[cfg(all(test, feature = "rayon"))]
+mod test {
+    use super::*;
+    use rayon::iter::IntoParallelIterator;
+
+    #[test]
+    fn windows_are_well_ordered() {
+        let len =
+            3 * NUMS_PER_BUFFER * rayon::current_num_threads() + parallel::DEFAULT_THRESHOLD + 7;
+        let mut written = String::new();
+        write_all(
+            (1..len + 1).into_par_iter(),
+            None,
+            ",",
+            crate::FizzBuzz::fizzbuzz,
+            |text| {
+                written.push_str(text);
+                Ok::<_, ()>(())
+            },
+        )
+        .unwrap();
+        let expected: Vec<String> = (1..len + 1)
+            .map(|n| crate::FizzBuzz::fizzbuzz(&n).to_string())
+            .collect();
+        assert_eq!(written, expected.join(","));
+    }
+}
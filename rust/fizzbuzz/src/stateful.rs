@@ -0,0 +1,242 @@
+//! Stateful rules, whose answers depend on the answers which came before.
+//!
+//! Party variants such as "every third fizz becomes bang" cannot be expressed as a pure `map`
+//! over the inputs. A `StatefulRule` instead describes:
+//! - the contribution of each number to a `Monoid` state (`observe`), and
+//! - how to adjust each answer given the combined state of every earlier number (`answer`).
+//!
+//! Because the state is an associative monoid, the combined states can be calculated with a
+//! parallel prefix scan, so `MultiFizzBuzz::fizzbuzz_scan()` still makes use of `rayon`.
+//! States which are not associative can set `Monoid::ASSOCIATIVE = false` to be scanned sequentially.
+//!
+//! Without the `rayon` feature, `SequentialFizzBuzz::fizzbuzz_seq_scan()` applies the same rules
+//! sequentially.
+//!
+//! ```
+//! # #[cfg(feature = "rayon")] {
+//! use fizzbuzz::MultiFizzBuzz;
+//! use fizzbuzz::stateful::EveryNth;
+//! use rayon::iter::ParallelIterator;
+//!
+//! let bang = EveryNth::new("fizz", 3, "bang");
+//! let answers: Vec<String> = (1..13).fizzbuzz_scan(&bang).collect();
+//! assert_eq!(answers[8], "bang"); // 9 is the third fizz
+//! assert_eq!(answers[11], "fizz"); // 12 is the fourth fizz
+//! # }
+//! ```
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+#[cfg(feature = "rayon")]
+use crate::parallel;
+use crate::{FizzBuzzAnswer, Word};
+
+/// A state which can be combined with others, with an identity element.
+///
+/// Implementations must ensure that:
+/// - `Self::empty().combine(x) == x == x.combine(&Self::empty())`
+/// - `a.combine(b).combine(c) == a.combine(b.combine(c))` (associativity)
+///
+/// If `combine` is not associative then set `ASSOCIATIVE` to `false` and the state will be
+/// combined sequentially, from left to right.
+pub trait Monoid: Clone + Send + Sync {
+    /// `false` if `combine` is not associative and must therefore be evaluated sequentially
+    const ASSOCIATIVE: bool = true;
+
+    /// The identity element: the state before any numbers have been seen
+    fn empty() -> Self;
+
+    /// Combines this state with the `next` state
+    fn combine(&self, next: &Self) -> Self;
+}
+
+/// A rule whose answer depends upon the answers given for earlier numbers.
+pub trait StatefulRule<Num>: Sync {
+    type State: Monoid;
+
+    /// The contribution of `num`, with its stateless `answer`, to the state.
+    fn observe(&self, num: &Num, answer: &FizzBuzzAnswer<Num>) -> Self::State;
+
+    /// Adjusts the stateless `answer` for `num` given the combined state of every earlier number.
+    fn answer(
+        &self,
+        before: &Self::State,
+        num: &Num,
+        answer: FizzBuzzAnswer<Num>,
+    ) -> FizzBuzzAnswer<Num>;
+}
+
+/// A count of occurrences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Count(pub usize);
+
+impl Monoid for Count {
+    fn empty() -> Self {
+        Count(0)
+    }
+
+    fn combine(&self, next: &Self) -> Self {
+        Count(self.0 + next.0)
+    }
+}
+
+/// Replaces every `n`th occurrence of a word with a different word.
+///
+/// E.g. `EveryNth::new("fizz", 3, "bang")` turns every third "fizz" into "bang".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EveryNth {
+    word: Word,
+    n: usize,
+    replacement: Word,
+}
+
+impl EveryNth {
+    /// # Panics
+    /// If `n` is zero.
+    pub fn new(word: impl Into<Word>, n: usize, replacement: impl Into<Word>) -> Self {
+        assert!(n > 0, "cannot replace every 0th word");
+        Self {
+            word: word.into(),
+            n,
+            replacement: replacement.into(),
+        }
+    }
+
+    fn matches<Num>(&self, answer: &FizzBuzzAnswer<Num>) -> bool {
+        answer.word().is_some_and(|word| self.word == word)
+    }
+}
+
+impl<Num> StatefulRule<Num> for EveryNth {
+    type State = Count;
+
+    fn observe(&self, _num: &Num, answer: &FizzBuzzAnswer<Num>) -> Count {
+        Count(self.matches(answer).into())
+    }
+
+    fn answer(
+        &self,
+        before: &Count,
+        _num: &Num,
+        answer: FizzBuzzAnswer<Num>,
+    ) -> FizzBuzzAnswer<Num> {
+        if self.matches(&answer) && (before.0 + 1).is_multiple_of(self.n) {
+            FizzBuzzAnswer::from_word(Word::clone(&self.replacement))
+        } else {
+            answer
+        }
+    }
+}
+
+/// Replaces every state in `states` with the combination of all states before it.
+///
+/// Evaluated in parallel, in chunks, if the state is associative and there are enough states.
+#[cfg(feature = "rayon")]
+fn exclusive_scan<State: Monoid>(states: &mut [State]) {
+    fn scan_chunk<State: Monoid>(start: State, chunk: &mut [State]) {
+        let mut acc = start;
+        for state in chunk {
+            let next = acc.combine(state);
+            *state = core::mem::replace(&mut acc, next);
+        }
+    }
+
+    // An empty input would make empty chunks, which rayon rejects
+    if !State::ASSOCIATIVE || states.is_empty() || states.len() < parallel::threshold() {
+        return scan_chunk(State::empty(), states);
+    }
+    let chunk_size = states.len().div_ceil(rayon::current_num_threads());
+    let totals: Vec<State> = states
+        .par_chunks(chunk_size)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .fold(State::empty(), |acc, state| acc.combine(state))
+        })
+        .collect();
+    let mut starts = totals;
+    scan_chunk(State::empty(), &mut starts);
+    states
+        .par_chunks_mut(chunk_size)
+        .zip(starts)
+        .for_each(|(chunk, start)| scan_chunk(start, chunk));
+}
+
+/// Applies a stateful `rule` to the stateless answers for `nums`.
+#[cfg(feature = "rayon")]
+pub(crate) fn scan<'r, Answers, Num, Rule, Rtn>(
+    answers: Answers,
+    rule: &'r Rule,
+) -> impl IndexedParallelIterator<Item = Rtn> + use<'r, Answers, Num, Rule, Rtn>
+where
+    Answers: IndexedParallelIterator<Item = (Num, FizzBuzzAnswer<Num>)>,
+    Num: Send + Sync,
+    Rule: StatefulRule<Num>,
+    Rtn: From<FizzBuzzAnswer<Num>> + Send,
+{
+    let answers: Vec<(Num, FizzBuzzAnswer<Num>)> = answers.collect();
+    let mut states: Vec<Rule::State> = answers
+        .par_iter()
+        .with_min_len(crate::min_len(answers.len()))
+        .map(|(num, answer)| rule.observe(num, answer))
+        .collect();
+    exclusive_scan(&mut states);
+    let min_len = crate::min_len(answers.len());
+    answers
+        .into_par_iter()
+        .zip(states)
+        .with_min_len(min_len)
+        .map(|((num, answer), before)| rule.answer(&before, &num, answer).into())
+}
+
+#[cfg(all(test, feature = "rayon"))]
+mod test {
+    use super::*;
+
+    /// Composition of affine functions `x -> mul * x + add` is associative but not commutative,
+    /// so catches any ordering errors
+    #[derive(Debug, Clone, PartialEq)]
+    struct Affine {
+        mul: u64,
+        add: u64,
+    }
+
+    impl Monoid for Affine {
+        fn empty() -> Self {
+            Affine { mul: 1, add: 0 }
+        }
+
+        fn combine(&self, next: &Self) -> Self {
+            Affine {
+                mul: next.mul.wrapping_mul(self.mul),
+                add: next.mul.wrapping_mul(self.add).wrapping_add(next.add),
+            }
+        }
+    }
+
+    #[test]
+    fn big_scan_is_well_ordered() {
+        let mut states: Vec<Affine> = (0..parallel::DEFAULT_THRESHOLD as u64 + 2)
+            .map(|i| Affine {
+                mul: i % 7 + 1,
+                add: i % 11,
+            })
+            .collect();
+        let mut expected = Vec::with_capacity(states.len());
+        let mut acc = Affine::empty();
+        for state in &states {
+            expected.push(acc.clone());
+            acc = acc.combine(state);
+        }
+        exclusive_scan(&mut states);
+        assert_eq!(states, expected);
+    }
+
+    #[test]
+    fn empty_scan_always_parallel() {
+        let mut states: Vec<Affine> = vec![];
+        parallel::with_threshold(0, || exclusive_scan(&mut states));
+        assert!(states.is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+//! Parallel answers for inputs whose length is not known in advance.
+
+use rayon::prelude::*;
+
+use crate::{game::StaticRules, FizzBuzz, FizzBuzzAnswer, RuleSet};
+
+/// Used to obtain the answers for the elements of any rayon `ParallelIterator`, including those
+/// which are not indexed, such as the output of `filter()` or `par_bridge()`, or a `HashSet`.
+///
+/// The answers are provided as a plain `ParallelIterator`, so they can't be zipped or collected
+/// into a preallocated `Vec`, but are still in the order of the input. They are evaluated lazily,
+/// when the returned iterator is consumed. Prefer `MultiFizzBuzz` for indexed inputs: knowing
+/// their length, it evaluates short inputs on a single thread.
+///
+/// The methods have distinct names, so both traits can be imported together. Iterators over
+/// references can be evaluated after `.copied()` or `.cloned()`.
+///
+/// ```
+/// use fizzbuzz::UnindexedFizzBuzz;
+/// use rayon::prelude::*;
+///
+/// let odd = (1..16).into_par_iter().filter(|n| n % 2 == 1);
+/// let fizzbuzzed: Vec<String> = odd.fizzbuzz_unindexed().collect();
+/// assert_eq!(fizzbuzzed, vec!["1", "fizz", "buzz", "7", "fizz", "11", "13", "fizzbuzz"]);
+///
+/// let fizzbuzzed: Vec<(u32, String)> = [5_u32, 6].into_par_iter().fizzbuzz_pairs().collect();
+/// assert_eq!(fizzbuzzed, vec![(5, "buzz".to_string()), (6, "fizz".to_string())]);
+/// ```
+pub trait UnindexedFizzBuzz {
+    /// The type of the numbers being fizzbuzzed
+    type Num;
+
+    /// Returns an iterator which provides the FizzBuzz values for the elements of the implementing
+    /// type.
+    ///
+    /// Note:
+    /// - This function **consumes** the input
+    /// - The returned iterator is a `rayon::iter::ParallelIterator`
+    /// - The Items in the returned iterator will be converted to a requested type
+    ///   (e.g. `FizzBuzzAnswer`, `String`, `Cow<str>`)
+    fn fizzbuzz_unindexed<Rtn>(self) -> impl ParallelIterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send;
+
+    /// As `fizzbuzz_unindexed()`, when playing with a custom `RuleSet`.
+    fn fizzbuzz_unindexed_with<Rtn>(
+        self,
+        rules: &RuleSet<Self::Num>,
+    ) -> impl ParallelIterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send;
+
+    /// As `fizzbuzz_unindexed()`, using a rule set which is fixed at compile time, such as
+    /// `game::Game<3, 7>`.
+    fn fizzbuzz_unindexed_as<Rules, Rtn>(self) -> impl ParallelIterator<Item = Rtn>
+    where
+        Rules: StaticRules,
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send;
+
+    /// As `fizzbuzz_unindexed()`, but provides each element alongside its answer, which
+    /// identifies the answers when the order of the input is not meaningful, as for a `HashSet`.
+    fn fizzbuzz_pairs<Rtn>(self) -> impl ParallelIterator<Item = (Self::Num, Rtn)>
+    where
+        Rtn: From<FizzBuzzAnswer<Self::Num>> + Send;
+}
+
+/// Implements the UnindexedFizzBuzz trait for any type which can be converted into a
+/// `rayon::iter::ParallelIterator` over Items which implement `fizzbuzz::FizzBuzz`
+impl<Iterable, Num> UnindexedFizzBuzz for Iterable
+where
+    Iterable: IntoParallelIterator<Item = Num>,
+    Num: FizzBuzz + Send,
+{
+    type Num = Num;
+
+    fn fizzbuzz_unindexed<Rtn>(self) -> impl ParallelIterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Num>> + Send,
+    {
+        self.into_par_iter().map(|n| n.fizzbuzz().into())
+    }
+
+    fn fizzbuzz_unindexed_with<Rtn>(self, rules: &RuleSet<Num>) -> impl ParallelIterator<Item = Rtn>
+    where
+        Rtn: From<FizzBuzzAnswer<Num>> + Send,
+    {
+        self.into_par_iter().map(|n| n.fizzbuzz_with(rules).into())
+    }
+
+    fn fizzbuzz_unindexed_as<Rules, Rtn>(self) -> impl ParallelIterator<Item = Rtn>
+    where
+        Rules: StaticRules,
+        Rtn: From<FizzBuzzAnswer<Num>> + Send,
+    {
+        self.into_par_iter()
+            .map(|n| n.fizzbuzz_as::<Rules>().into())
+    }
+
+    fn fizzbuzz_pairs<Rtn>(self) -> impl ParallelIterator<Item = (Num, Rtn)>
+    where
+        Rtn: From<FizzBuzzAnswer<Num>> + Send,
+    {
+        self.into_par_iter().map(|n| {
+            let answer = n.fizzbuzz().into();
+            (n, answer)
+        })
+    }
+}
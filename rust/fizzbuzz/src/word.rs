@@ -0,0 +1,93 @@
+//! The text of a `FizzBuzzAnswer::Word`.
+
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, string::String};
+use core::{fmt, ops::Deref};
+
+/// The text of a `FizzBuzzAnswer::Word`.
+///
+/// Created from a `&'static str` with `.into()`, which never allocates, and dereferences to `&str`.
+/// With the `alloc` feature it can also be created from, and converted into, a `String` or a
+/// `Cow<'static, str>`.
+///
+/// ```
+/// use fizzbuzz::Word;
+///
+/// let word: Word = "woof".into();
+/// assert_eq!(&*word, "woof");
+/// assert_eq!(word.len(), 4);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Word(Text);
+
+#[cfg(feature = "alloc")]
+type Text = Cow<'static, str>;
+
+#[cfg(not(feature = "alloc"))]
+type Text = &'static str;
+
+impl Deref for Word {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        Deref::deref(&self.0)
+    }
+}
+
+impl AsRef<str> for Word {
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl fmt::Display for Word {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self)
+    }
+}
+
+impl PartialEq<str> for Word {
+    fn eq(&self, other: &str) -> bool {
+        **self == *other
+    }
+}
+
+impl PartialEq<&str> for Word {
+    fn eq(&self, other: &&str) -> bool {
+        **self == **other
+    }
+}
+
+impl From<&'static str> for Word {
+    fn from(word: &'static str) -> Self {
+        Self(Text::from(word))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<String> for Word {
+    fn from(word: String) -> Self {
+        Self(word.into())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Cow<'static, str>> for Word {
+    fn from(word: Cow<'static, str>) -> Self {
+        Self(word)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Word> for Cow<'static, str> {
+    fn from(word: Word) -> Self {
+        word.0
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Word> for String {
+    fn from(word: Word) -> Self {
+        word.0.into_owned()
+    }
+}
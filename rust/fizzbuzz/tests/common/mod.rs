@@ -0,0 +1,54 @@
+//! Fixtures shared between the integration tests.
+//!
+//! Each test file only uses some of them.
+#![allow(dead_code)]
+
+use std::{
+    fmt::Display,
+    thread::{self, ThreadId},
+};
+
+use fizzbuzz::{FizzBuzz, FizzBuzzAnswer, RuleSet};
+
+/// The thread which produced an answer
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct OnThread {
+    pub id: ThreadId,
+    pub name: Option<String>,
+}
+
+impl OnThread {
+    /// The current thread
+    pub fn current() -> Self {
+        let thread = thread::current();
+        OnThread {
+            id: thread.id(),
+            name: thread.name().map(String::from),
+        }
+    }
+}
+
+impl<Num> From<FizzBuzzAnswer<Num>> for OnThread {
+    fn from(_: FizzBuzzAnswer<Num>) -> Self {
+        OnThread::current()
+    }
+}
+
+/// The answers expected from a `for` loop over `nums`
+pub fn expected<Num: FizzBuzz + Display>(nums: impl Iterator<Item = Num>) -> Vec<String> {
+    nums.map(|n| n.fizzbuzz().into()).collect()
+}
+
+/// The answers expected from a `for` loop over `nums`, playing with `rules`
+pub fn expected_with<Num: FizzBuzz + Display>(
+    nums: impl Iterator<Item = Num>,
+    rules: &RuleSet<Num>,
+) -> Vec<String> {
+    nums.map(|n| n.fizzbuzz_with(rules).into()).collect()
+}
+
+/// The lines expected from a `for` loop over `nums`
+pub fn expected_lines<Num: FizzBuzz + Display>(nums: impl Iterator<Item = Num>) -> Vec<u8> {
+    nums.flat_map(|n| format!("{}\n", n.fizzbuzz()).into_bytes())
+        .collect()
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "std")]
+
+use std::{collections::HashSet, num::NonZeroUsize};
+
+#[cfg(not(feature = "rayon"))]
+use fizzbuzz::BackendFizzBuzz;
+#[cfg(feature = "rayon")]
+use fizzbuzz::MultiFizzBuzz;
+use fizzbuzz::SequentialFizzBuzz;
+use fizzbuzz::{backend::Backend, game::Game, parallel, RuleSet};
+
+mod common;
+use common::OnThread;
+
+fn threads(count: usize) -> Backend {
+    Backend::Threads(NonZeroUsize::new(count).unwrap())
+}
+
+#[test]
+fn threads_are_well_ordered() {
+    let expected: Vec<String> = (1..100_001).fizzbuzz_seq().collect();
+    for count in [1, 2, 3, 7] {
+        let answers: Vec<String> =
+            parallel::with_threshold(0, || (1..100_001).fizzbuzz_on(threads(count)));
+        assert_eq!(answers, expected, "with {count} threads");
+    }
+}
+
+#[test]
+fn more_threads_than_numbers() {
+    let answers: Vec<String> = parallel::with_threshold(0, || (1..4).fizzbuzz_on(threads(8)));
+    assert_eq!(answers, vec!["1", "2", "fizz"]);
+}
+
+#[test]
+fn empty() {
+    let answers: Vec<String> = parallel::with_threshold(0, || (1..1).fizzbuzz_on(threads(4)));
+    assert!(answers.is_empty());
+}
+
+#[test]
+fn small_inputs_stay_on_current_thread() {
+    let answers: HashSet<OnThread> =
+        parallel::with_threshold(1_000, || (1..1_000).fizzbuzz_on(threads(4)))
+            .into_iter()
+            .collect();
+    assert_eq!(answers, HashSet::from([OnThread::current()]));
+
+    let answers: HashSet<OnThread> =
+        parallel::with_threshold(1_000, || (1..1_001).fizzbuzz_on(threads(4)))
+            .into_iter()
+            .collect();
+    assert_eq!(answers.len(), 4);
+    assert!(!answers.contains(&OnThread::current()));
+}
+
+#[test]
+fn custom_and_static_rules() {
+    let rules = RuleSet::default().divisor(7, "woof");
+    let answers: Vec<String> = vec![5, 7, 35, 105].fizzbuzz_with_on(&rules, threads(2));
+    assert_eq!(answers, vec!["buzz", "woof", "buzzwoof", "fizzbuzzwoof"]);
+    let answers: Vec<String> = (6..11).fizzbuzz_as_on::<Game<3, 7>, _>(threads(2));
+    assert_eq!(answers, vec!["fizz", "buzz", "8", "fizz", "10"]);
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn backends_match() {
+    assert_eq!(Backend::default(), Backend::Rayon);
+    let rayon: Vec<fizzbuzz::FizzBuzzAnswer<u32>> =
+        parallel::with_threshold(0, || (1..50_000_u32).fizzbuzz_on(Backend::Rayon));
+    let threads: Vec<fizzbuzz::FizzBuzzAnswer<u32>> =
+        parallel::with_threshold(0, || (1..50_000_u32).fizzbuzz_on(threads(3)));
+    assert_eq!(rayon, threads);
+}
+
+#[test]
+#[cfg(not(feature = "rayon"))]
+fn default_is_threads() {
+    assert_eq!(Backend::default(), Backend::threads());
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn threads_on_indexed_iterator() {
+    use rayon::prelude::*;
+
+    let answers: Vec<String> = parallel::with_threshold(0, || {
+        (0..30_u32)
+            .into_par_iter()
+            .map(|n| n + 1)
+            .fizzbuzz_on(threads(4))
+    });
+    let expected: Vec<String> = (1..=30_u32).fizzbuzz_seq().collect();
+    assert_eq!(answers, expected);
+}
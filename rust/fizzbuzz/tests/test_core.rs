@@ -0,0 +1,128 @@
+//! The parts of the crate which are available without `std` or an allocator
+
+use core::fmt::{self, Write};
+
+use fizzbuzz::{
+    game::{Game, StaticRules},
+    FizzBuzz, FizzBuzzAnswer, SequentialFizzBuzz,
+};
+
+/// A `fmt::Write` into a fixed-size buffer, as used on a microcontroller
+struct Fixed<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> Fixed<N> {
+    fn new() -> Self {
+        Self {
+            buf: [0; N],
+            len: 0,
+        }
+    }
+
+    fn text(&self) -> &str {
+        core::str::from_utf8(&self.buf[..self.len]).unwrap()
+    }
+}
+
+impl<const N: usize> Write for Fixed<N> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        let end = self.len + text.len();
+        self.buf
+            .get_mut(self.len..end)
+            .ok_or(fmt::Error)?
+            .copy_from_slice(text.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+/// Says "fizz", "woof" or "fizzwoof", with every word fixed at compile time
+struct Woof;
+
+impl StaticRules for Woof {
+    const RULES: &'static [(u8, &'static str)] = &[(3, "fizz"), (7, "woof")];
+
+    fn answer<Num>(num: &Num) -> FizzBuzzAnswer<Num>
+    where
+        Num: TryFrom<u8> + fmt::Display + PartialEq + Clone,
+        for<'a> &'a Num: core::ops::Rem<Num, Output = Num>,
+    {
+        match Game::<3, 7>::answer(num) {
+            FizzBuzzAnswer::Fizz => FizzBuzzAnswer::Word("fizz".into()),
+            FizzBuzzAnswer::Buzz => FizzBuzzAnswer::Word("woof".into()),
+            FizzBuzzAnswer::Fizzbuzz => FizzBuzzAnswer::Word("fizzwoof".into()),
+            answer => answer,
+        }
+    }
+}
+
+#[test]
+fn write_into_buffer() {
+    let mut buf = [0; 8];
+    assert_eq!(1.write_fizzbuzz_buf(&mut buf), Ok("1"));
+    assert_eq!(3.write_fizzbuzz_buf(&mut buf), Ok("fizz"));
+    assert_eq!(5_u8.write_fizzbuzz_buf(&mut buf), Ok("buzz"));
+    assert_eq!(15_i64.write_fizzbuzz_buf(&mut buf), Ok("fizzbuzz"));
+    assert_eq!((-9_876_542).write_fizzbuzz_buf(&mut buf), Ok("-9876542"));
+}
+
+#[test]
+fn buffer_too_short() {
+    let mut buf = [0; 4];
+    assert_eq!(1234.write_fizzbuzz_buf(&mut buf), Ok("1234"));
+    assert_eq!(12345.write_fizzbuzz_buf(&mut buf), Err(fmt::Error));
+    assert_eq!(15.write_fizzbuzz_buf(&mut buf), Err(fmt::Error));
+}
+
+#[test]
+fn largest_numbers_fit_in_40_bytes() {
+    let mut buf = [0; 40];
+    assert_eq!(
+        i128::MIN.write_fizzbuzz_buf(&mut buf),
+        Ok("-170141183460469231731687303715884105728")
+    );
+    assert_eq!(
+        (u128::MAX - 1).write_fizzbuzz_buf(&mut buf),
+        Ok("340282366920938463463374607431768211454")
+    );
+}
+
+#[test]
+fn non_primitive_numbers() {
+    let mut buf = [0; 8];
+    assert_eq!(2.5_f32.write_fizzbuzz_buf(&mut buf), Ok("2.5"));
+    assert_eq!(30_f64.write_fizzbuzz_buf(&mut buf), Ok("fizzbuzz"));
+}
+
+#[test]
+fn write_fmt_into_fixed_sink() {
+    let mut out = Fixed::<64>::new();
+    (1..16).write_fizzbuzz_seq_fmt(&mut out, " ").unwrap();
+    assert_eq!(
+        out.text(),
+        "1 2 fizz 4 buzz fizz 7 8 fizz buzz 11 fizz 13 14 fizzbuzz"
+    );
+
+    let mut full = Fixed::<16>::new();
+    assert_eq!(
+        (1..16).write_fizzbuzz_seq_fmt(&mut full, " "),
+        Err(fmt::Error)
+    );
+}
+
+#[test]
+fn static_words() {
+    let mut answers = (20..=22).fizzbuzz_seq_as::<Woof, FizzBuzzAnswer<i32>>();
+    assert_eq!(answers.next(), Some(FizzBuzzAnswer::Number(20)));
+    assert_eq!(
+        answers.next(),
+        Some(FizzBuzzAnswer::Word("fizzwoof".into()))
+    );
+    assert_eq!(answers.next(), Some(FizzBuzzAnswer::Number(22)));
+
+    let mut out = Fixed::<8>::new();
+    write!(out, "{}", 7.fizzbuzz_as::<Woof>()).unwrap();
+    assert_eq!(out.text(), "woof");
+}
@@ -1,10 +1,12 @@
 #![cfg(test)]
 use fizzbuzz::FizzBuzz;
+#[cfg(feature = "alloc")]
 use googletest::prelude::*;
 
 /// Create a test case which tests all numbers between 1 and 255 for a given type
 ///
 /// Call with `test_this! {test_case_name: type, ...}`
+#[cfg(feature = "alloc")]
 macro_rules! test_this {
     ($($id:ident: $t:ty),*) => {
         $(
@@ -33,6 +35,7 @@ macro_rules! test_this {
 }
 
 /// Test all compatible standard types
+#[cfg(feature = "alloc")]
 mod standard_types_up_to_127_as_strings {
     use fizzbuzz::FizzBuzzAnswer;
 
@@ -58,27 +61,39 @@ mod standard_types_up_to_127_as_strings {
     #[test]
     fn test_negative() {
         assert_eq!(
-            <FizzBuzzAnswer as Into<String>>::into((-1_i8).fizzbuzz()),
+            <FizzBuzzAnswer<_> as Into<String>>::into((-1_i8).fizzbuzz()),
             "-1".to_string()
         );
         assert_eq!(
-            <FizzBuzzAnswer as Into<String>>::into((-3_i8).fizzbuzz()),
+            <FizzBuzzAnswer<_> as Into<String>>::into((-3_i8).fizzbuzz()),
             "fizz".to_string()
         );
         assert_eq!(
-            <FizzBuzzAnswer as Into<String>>::into((-5_i8).fizzbuzz()),
+            <FizzBuzzAnswer<_> as Into<String>>::into((-5_i8).fizzbuzz()),
             "buzz".to_string()
         );
         assert_eq!(
-            <FizzBuzzAnswer as Into<String>>::into((-15_i8).fizzbuzz()),
+            <FizzBuzzAnswer<_> as Into<String>>::into((-15_i8).fizzbuzz()),
             "fizzbuzz".to_string()
         );
     }
 
+    #[test]
+    fn test_extremes() {
+        let answer: String = i128::MIN.fizzbuzz().into();
+        assert_eq!(answer, i128::MIN.to_string());
+        let answer: String = i64::MIN.fizzbuzz().into();
+        assert_eq!(answer, i64::MIN.to_string());
+        let answer: String = u128::MAX.fizzbuzz().into();
+        assert_eq!(answer, "fizzbuzz");
+        let answer: String = (u64::MAX - 1).fizzbuzz().into();
+        assert_eq!(answer, (u64::MAX - 1).to_string());
+    }
+
     #[test]
     fn test_not_whole_number() {
         assert_eq!(
-            <FizzBuzzAnswer as Into<String>>::into((3.2_f32).fizzbuzz()),
+            <FizzBuzzAnswer<_> as Into<String>>::into((3.2_f32).fizzbuzz()),
             "3.2".to_string()
         );
     }
@@ -86,12 +101,13 @@ mod standard_types_up_to_127_as_strings {
 
 /// Create a custom type based on i16, add the minimum set of non-derivable
 /// traits, impl_fizzbuzz! and test ...
+#[cfg(feature = "alloc")]
 mod custom_types_as_strings {
     use std::{fmt::Display, ops::Rem};
 
     use super::*;
 
-    #[derive(PartialEq)]
+    #[derive(PartialEq, Clone)]
     struct Myint(i16);
 
     impl Rem<Myint> for &Myint {
@@ -116,6 +132,71 @@ mod custom_types_as_strings {
     }
 }
 
+/// Numbers which are not `'static`
+mod borrowed_types {
+    use std::{fmt, ops::Rem};
+
+    use fizzbuzz::FizzBuzzAnswer;
+
+    use super::*;
+
+    /// A number which may borrow its value
+    #[derive(Clone)]
+    enum Num<'a> {
+        Owned(i32),
+        Borrowed(&'a i32),
+    }
+
+    impl Num<'_> {
+        fn value(&self) -> i32 {
+            match self {
+                Num::Owned(n) => *n,
+                Num::Borrowed(n) => **n,
+            }
+        }
+    }
+
+    impl PartialEq for Num<'_> {
+        fn eq(&self, other: &Self) -> bool {
+            self.value() == other.value()
+        }
+    }
+
+    impl<'a> Rem<Num<'a>> for &Num<'a> {
+        type Output = Num<'a>;
+        fn rem(self, rhs: Num<'a>) -> Self::Output {
+            Num::Owned(self.value() % rhs.value())
+        }
+    }
+
+    impl fmt::Display for Num<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.value().fmt(f)
+        }
+    }
+
+    impl From<u8> for Num<'_> {
+        fn from(value: u8) -> Self {
+            Num::Owned(value.into())
+        }
+    }
+
+    #[test]
+    fn not_static() {
+        let values: Vec<i32> = (1..=15).collect();
+        let answers: Vec<FizzBuzzAnswer<Num>> =
+            values.iter().map(|n| Num::Borrowed(n).fizzbuzz()).collect();
+        assert!(matches!(
+            answers[0],
+            FizzBuzzAnswer::Number(Num::Borrowed(&1))
+        ));
+        assert!(matches!(answers[2], FizzBuzzAnswer::Fizz));
+        assert!(matches!(answers[14], FizzBuzzAnswer::Fizzbuzz));
+        assert_eq!(format!("{}", answers[3]), "4");
+    }
+}
+
+#[cfg(feature = "alloc")]
 mod ints_as_cows {
     use std::borrow::Cow;
 
@@ -149,3 +230,53 @@ mod ints_as_cows {
         assert_eq!(answer, expected)
     }
 }
+
+mod answers {
+    use fizzbuzz::{FizzBuzzAnswer, Word};
+
+    use super::*;
+
+    #[test]
+    fn number_is_carried() {
+        assert_eq!(7.fizzbuzz(), FizzBuzzAnswer::Number(7));
+        assert_eq!(3.5_f64.fizzbuzz(), FizzBuzzAnswer::Number(3.5));
+        assert_eq!(15_u8.fizzbuzz(), FizzBuzzAnswer::Fizzbuzz);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(format!("{}", (-7).fizzbuzz()), "-7");
+        assert_eq!(format!("{}", 3.2_f64.fizzbuzz()), "3.2");
+        assert_eq!(format!("{}", 9.fizzbuzz()), "fizz");
+        assert_eq!(format!("{:>6}", 10.fizzbuzz()), "  buzz");
+        assert_eq!(format!("{:>6}", 11.fizzbuzz()), "    11");
+        assert_eq!(format!("{}", 30_u128.fizzbuzz()), "fizzbuzz");
+    }
+
+    #[test]
+    fn count_without_formatting() {
+        let numbers = (1..=100)
+            .map(|n: i32| n.fizzbuzz())
+            .filter(|answer| matches!(answer, FizzBuzzAnswer::Number(_)))
+            .count();
+        assert_eq!(numbers, 53);
+    }
+
+    #[test]
+    fn words() {
+        let word: Word = "woof".into();
+        assert_eq!(word, "woof");
+        assert_eq!(format!("{word:>6}"), "  woof");
+        let answer: FizzBuzzAnswer<i32> = FizzBuzzAnswer::Word(word);
+        assert_eq!(answer.word(), Some("woof"));
+        assert_eq!(format!("{answer}"), "woof");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn owned_words() {
+        let word = Word::from(String::from("wo") + "of");
+        assert_eq!(word, Word::from("woof"));
+        assert_eq!(String::from(word), "woof");
+    }
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "rayon")]
+
+use fizzbuzz::game::{Classic, Game, StaticRules};
+use fizzbuzz::{FizzBuzz, FizzBuzzAnswer, MultiFizzBuzz, RuleSet};
+use rayon::iter::ParallelIterator;
+
+struct Woof;
+
+impl StaticRules for Woof {
+    const RULES: &'static [(u8, &'static str)] = &[(3, "fizz"), (5, "buzz"), (7, "woof")];
+}
+
+mod game {
+    use super::*;
+
+    #[test]
+    fn classic_matches_fizzbuzz() {
+        for i in -100..100_i64 {
+            assert_eq!(i.fizzbuzz_as::<Classic>(), i.fizzbuzz(), "for {i}")
+        }
+        for i in 0..=u8::MAX {
+            assert_eq!(i.fizzbuzz_as::<Game<3, 5>>(), i.fizzbuzz(), "for {i}")
+        }
+    }
+
+    #[test]
+    fn other_divisors() {
+        let answers: Vec<FizzBuzzAnswer<_>> = [1, 2, 7, 14]
+            .iter()
+            .map(|n| n.fizzbuzz_as::<Game<2, 7>>())
+            .collect();
+        assert_eq!(
+            answers,
+            vec![
+                FizzBuzzAnswer::Number(1),
+                FizzBuzzAnswer::Fizz,
+                FizzBuzzAnswer::Buzz,
+                FizzBuzzAnswer::Fizzbuzz
+            ]
+        )
+    }
+
+    #[test]
+    fn divisor_too_big_for_type() {
+        let answer: String = 100_i8.fizzbuzz_as::<Game<5, 200>>().into();
+        assert_eq!(answer, "100")
+    }
+
+    #[test]
+    fn float() {
+        let answer: String = 3.2_f64.fizzbuzz_as::<Classic>().into();
+        assert_eq!(answer, "3.2");
+        let answer: String = 14_f64.fizzbuzz_as::<Game<2, 7>>().into();
+        assert_eq!(answer, "fizzbuzz");
+    }
+}
+
+mod static_rules {
+    use super::*;
+
+    #[test]
+    fn matches_rule_set() {
+        let rules = RuleSet::default().divisor(7, "woof");
+        for i in -150..150_i32 {
+            assert_eq!(i.fizzbuzz_as::<Woof>(), i.fizzbuzz_with(&rules), "for {i}")
+        }
+    }
+
+    #[test]
+    fn single_word_is_not_allocated() {
+        assert_eq!(21.fizzbuzz_as::<Woof>().word(), Some("fizzwoof"));
+        let FizzBuzzAnswer::Word(word) = 7.fizzbuzz_as::<Woof>() else {
+            panic!("7 is woof")
+        };
+        assert!(matches!(
+            std::borrow::Cow::from(word),
+            std::borrow::Cow::Borrowed("woof")
+        ));
+    }
+}
+
+mod multi {
+    use super::*;
+
+    #[test]
+    fn classic_matches_fizzbuzz() {
+        let classic: Vec<String> = (1..1_000_000_i32).fizzbuzz().collect();
+        let game: Vec<String> = (1..1_000_000_i32).fizzbuzz_as::<Classic, _>().collect();
+        assert_eq!(game, classic)
+    }
+
+    #[test]
+    fn custom_rules() {
+        let answers: Vec<String> = vec![1, 3, 7, 21, 35, 105]
+            .fizzbuzz_as::<Woof, _>()
+            .collect();
+        assert_eq!(
+            answers,
+            vec!["1", "fizz", "woof", "fizzwoof", "buzzwoof", "fizzbuzzwoof"]
+        )
+    }
+}
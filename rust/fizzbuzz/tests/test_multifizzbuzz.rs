@@ -1,3 +1,5 @@
+#![cfg(feature = "rayon")]
+
 use fizzbuzz::MultiFizzBuzz;
 use rayon::iter::ParallelIterator;
 
@@ -61,3 +63,207 @@ mod ranges {
         assert_eq!(answer, expected)
     }
 }
+
+mod borrowed {
+    use std::collections::VecDeque;
+
+    use fizzbuzz::{game::Game, BorrowedFizzBuzz, FizzBuzz, FizzBuzzAnswer, RuleSet};
+    use rayon::iter::IntoParallelRefIterator;
+
+    use super::*;
+
+    #[test]
+    fn test_borrowed_vec() {
+        let input = vec![1, 2, 3, 4, 5];
+        let answer: Vec<String> = (&input).fizzbuzz().collect();
+        assert_eq!(answer, vec!["1", "2", "fizz", "4", "buzz"]);
+        let answer: Vec<String> = input.fizzbuzz().collect();
+        assert_eq!(answer, vec!["1", "2", "fizz", "4", "buzz"]);
+    }
+
+    #[test]
+    fn test_slice() {
+        let input = [10_u64, 11, 12, 13, 14, 15];
+        let answer: Vec<FizzBuzzAnswer<u64>> = input[3..].fizzbuzz().collect();
+        assert_eq!(
+            answer,
+            vec![
+                FizzBuzzAnswer::Number(13),
+                FizzBuzzAnswer::Number(14),
+                FizzBuzzAnswer::Fizzbuzz
+            ]
+        );
+        assert_eq!(input.len(), 6);
+    }
+
+    #[test]
+    fn test_vec_deque() {
+        let mut input: VecDeque<i32> = (1..=10).collect();
+        input.rotate_left(3);
+        let answer: Vec<String> = input.par_iter().fizzbuzz().collect();
+        let expected: Vec<String> = input.iter().map(|n| n.fizzbuzz().into()).collect();
+        assert_eq!(answer, expected);
+    }
+
+    #[test]
+    fn test_big_vec_is_well_ordered() {
+        let input: Vec<usize> = (0..fizzbuzz::parallel::DEFAULT_THRESHOLD * 3).collect();
+        let answer: Vec<FizzBuzzAnswer<usize>> = (&input).fizzbuzz().collect();
+        let expected: Vec<FizzBuzzAnswer<usize>> = input.clone().fizzbuzz().collect();
+        assert_eq!(answer, expected);
+    }
+
+    #[test]
+    fn test_rules() {
+        let input = vec![5, 7, 35, 105];
+        let rules = RuleSet::default().divisor(7, "woof");
+        let answer: Vec<String> = (&input).fizzbuzz_with(&rules).collect();
+        assert_eq!(answer, vec!["buzz", "woof", "buzzwoof", "fizzbuzzwoof"]);
+        let answer: Vec<String> = (&input).fizzbuzz_as::<Game<5, 7>, _>().collect();
+        assert_eq!(answer, vec!["fizz", "buzz", "fizzbuzz", "fizzbuzz"]);
+    }
+}
+
+mod unindexed {
+    use std::collections::HashSet;
+
+    use fizzbuzz::{game::Game, FizzBuzz, FizzBuzzAnswer, RuleSet, UnindexedFizzBuzz};
+    use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelBridge};
+
+    use super::*;
+
+    #[test]
+    fn test_filter() {
+        let input = vec![1, 2, 3, 4, 5, 6];
+        let answer: Vec<String> = input
+            .par_iter()
+            .filter(|n| *n % 2 == 0)
+            .copied()
+            .fizzbuzz_unindexed()
+            .collect();
+        assert_eq!(answer, vec!["2", "4", "fizz"]);
+    }
+
+    #[test]
+    fn test_big_filter_is_well_ordered() {
+        let input = (0..fizzbuzz::parallel::DEFAULT_THRESHOLD * 3).into_par_iter();
+        let answer: Vec<FizzBuzzAnswer<usize>> =
+            input.filter(|n| n % 7 != 0).fizzbuzz_unindexed().collect();
+        let expected: Vec<FizzBuzzAnswer<usize>> = (0..fizzbuzz::parallel::DEFAULT_THRESHOLD * 3)
+            .filter(|n| n % 7 != 0)
+            .map(|n| n.fizzbuzz())
+            .collect();
+        assert_eq!(answer, expected);
+    }
+
+    #[test]
+    fn test_hash_set() {
+        let input: HashSet<u32> = (1..=15).collect();
+        let mut answer: Vec<(u32, String)> = input.fizzbuzz_pairs().collect();
+        answer.sort();
+        let expected: Vec<(u32, String)> = (1..=15).map(|n| (n, n.fizzbuzz().into())).collect();
+        assert_eq!(answer, expected);
+    }
+
+    #[test]
+    fn test_par_bridge() {
+        let answer: HashSet<String> = (1..=5).par_bridge().fizzbuzz_unindexed().collect();
+        let expected: HashSet<String> = ["1", "2", "fizz", "4", "buzz"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(answer, expected);
+    }
+
+    #[test]
+    fn test_indexed_input() {
+        let answer: Vec<String> = vec![3, 5, 7].fizzbuzz_unindexed().collect();
+        assert_eq!(answer, vec!["fizz", "buzz", "7"]);
+        let answer: Vec<String> = vec![3, 5, 7].fizzbuzz().collect();
+        assert_eq!(answer, vec!["fizz", "buzz", "7"]);
+    }
+
+    #[test]
+    fn test_filter_is_evaluated_when_consumed() {
+        let threads = rayon::current_num_threads() + 1;
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        let answers = (1..100)
+            .into_par_iter()
+            .filter(|n| {
+                assert_eq!(
+                    rayon::current_num_threads(),
+                    threads,
+                    "filtered outside the pool"
+                );
+                n % 2 == 0
+            })
+            .fizzbuzz_unindexed::<String>();
+        let answers: Vec<String> = pool.install(|| answers.collect());
+        assert_eq!(answers[..3], ["2", "4", "fizz"]);
+    }
+
+    #[test]
+    fn test_rules() {
+        let input = (1..=105).into_par_iter().filter(|n| n % 35 == 0);
+        let rules = RuleSet::default().divisor(7, "woof");
+        let answer: Vec<String> = input.clone().fizzbuzz_unindexed_with(&rules).collect();
+        assert_eq!(answer, vec!["buzzwoof", "buzzwoof", "fizzbuzzwoof"]);
+        let answer: Vec<String> = input.fizzbuzz_unindexed_as::<Game<5, 7>, _>().collect();
+        assert_eq!(answer, vec!["fizzbuzz", "fizzbuzz", "fizzbuzz"]);
+    }
+}
+
+mod into {
+    use fizzbuzz::FizzBuzzAnswer;
+
+    use super::*;
+
+    #[test]
+    fn test_into_slice() {
+        let mut out = vec![FizzBuzzAnswer::Number(0); 5];
+        vec![1, 2, 3, 4, 5].fizzbuzz_into(&mut out);
+        assert_eq!(
+            out,
+            vec![
+                FizzBuzzAnswer::Number(1),
+                FizzBuzzAnswer::Number(2),
+                FizzBuzzAnswer::Fizz,
+                FizzBuzzAnswer::Number(4),
+                FizzBuzzAnswer::Buzz
+            ]
+        );
+        (11..16).fizzbuzz_into(&mut out);
+        assert_eq!(out[4], FizzBuzzAnswer::Fizzbuzz);
+    }
+
+    #[test]
+    fn test_big_into_slice_is_well_ordered() {
+        let len = fizzbuzz::parallel::DEFAULT_THRESHOLD * 3;
+        let mut out = vec![String::new(); len];
+        (0..len).fizzbuzz_into(&mut out);
+        let expected: Vec<String> = (0..len).fizzbuzz().collect();
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "same length")]
+    fn test_into_slice_wrong_length() {
+        let mut out: Vec<String> = vec![String::new(); 4];
+        (1..6).fizzbuzz_into(&mut out);
+    }
+
+    #[test]
+    fn test_into_vec_reuses_storage() {
+        let mut out: Vec<String> = Vec::with_capacity(100);
+        let storage = out.as_ptr();
+        (1..101).fizzbuzz_into_vec(&mut out);
+        assert_eq!(out.len(), 100);
+        assert_eq!(out[99], "buzz");
+        vec![3, 5].fizzbuzz_into_vec(&mut out);
+        assert_eq!(out, vec!["fizz", "buzz"]);
+        assert_eq!(out.as_ptr(), storage);
+    }
+}
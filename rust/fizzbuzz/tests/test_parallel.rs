@@ -0,0 +1,156 @@
+#![cfg(feature = "rayon")]
+
+use fizzbuzz::{parallel, FizzBuzzAnswer, MultiFizzBuzz};
+use rayon::iter::ParallelIterator;
+
+mod common;
+
+/// The global threshold is shared by every test in this file, so it is only changed here
+#[test]
+fn global_threshold() {
+    assert_eq!(parallel::threshold(), parallel::DEFAULT_THRESHOLD);
+    parallel::set_threshold(10);
+    assert_eq!(parallel::threshold(), 10);
+    let answers: Vec<String> = (1..100).fizzbuzz().collect();
+    assert_eq!(answers[98], "fizz");
+
+    let calibrated = parallel::calibrate::<FizzBuzzAnswer<usize>>();
+    assert_eq!(parallel::threshold(), calibrated);
+    if rayon::current_num_threads() == 1 {
+        assert_eq!(calibrated, usize::MAX);
+    }
+    parallel::set_threshold(parallel::DEFAULT_THRESHOLD);
+}
+
+#[test]
+fn scoped_threshold() {
+    parallel::with_threshold(7, || {
+        parallel::with_threshold(0, || {
+            assert_eq!(parallel::threshold(), 0);
+            parallel::with_threshold(usize::MAX, || assert_eq!(parallel::threshold(), usize::MAX));
+            assert_eq!(parallel::threshold(), 0);
+        });
+        assert_eq!(parallel::threshold(), 7);
+    });
+}
+
+#[test]
+fn scoped_threshold_is_restored_after_panic() {
+    parallel::with_threshold(7, || {
+        let result = std::panic::catch_unwind(|| parallel::with_threshold(5, || panic!("oops")));
+        assert!(result.is_err());
+        assert_eq!(parallel::threshold(), 7);
+    });
+}
+
+#[test]
+fn threshold_applies_to_returned_iterators() {
+    let answers = parallel::with_threshold(0, || (1..10_000).fizzbuzz::<String>());
+    let answers: Vec<String> = answers.collect();
+    assert_eq!(answers.len(), 9_999);
+    assert_eq!(answers[14], "fizzbuzz");
+}
+
+#[test]
+fn always_parallel_is_well_ordered() {
+    let answers: Vec<FizzBuzzAnswer<i32>> =
+        parallel::with_threshold(0, || (1..50_000).fizzbuzz().collect());
+    let expected: Vec<FizzBuzzAnswer<i32>> =
+        parallel::with_threshold(usize::MAX, || (1..50_000).fizzbuzz().collect());
+    assert_eq!(answers, expected);
+    let writes = parallel::with_threshold(0, || {
+        let mut out = String::new();
+        (1..50_000).write_fizzbuzz_fmt(&mut out, ",").unwrap();
+        out
+    });
+    assert_eq!(
+        writes,
+        expected
+            .iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+}
+
+mod pools {
+    use std::{
+        collections::HashSet,
+        fmt::{self, Display},
+        ops::Rem,
+        sync::Mutex,
+    };
+
+    use super::*;
+    use common::OnThread;
+
+    /// The names of the threads which formatted a `Spy`
+    static FORMATTED_ON: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    /// An integer which records the threads on which it is formatted
+    #[derive(Clone, PartialEq)]
+    struct Spy(i32);
+
+    impl TryFrom<u8> for Spy {
+        type Error = ();
+
+        fn try_from(value: u8) -> Result<Self, ()> {
+            Ok(Spy(value.into()))
+        }
+    }
+
+    impl Rem<Spy> for &Spy {
+        type Output = Spy;
+
+        fn rem(self, rhs: Spy) -> Spy {
+            Spy(self.0 % rhs.0)
+        }
+    }
+
+    impl Display for Spy {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let name = std::thread::current()
+                .name()
+                .unwrap_or_default()
+                .to_string();
+            FORMATTED_ON.lock().unwrap().push(name);
+            self.0.fmt(f)
+        }
+    }
+
+    fn single_thread() -> rayon::ThreadPool {
+        parallel::pool_builder().num_threads(1).build().unwrap()
+    }
+
+    #[test]
+    fn answers_on_dedicated_pool() {
+        let pool = single_thread();
+        let answers: Vec<OnThread> =
+            parallel::with_threshold(0, || (1..100_000).fizzbuzz_in(&pool));
+        let threads: HashSet<String> = answers
+            .into_iter()
+            .map(|answer| answer.name.unwrap_or_default())
+            .collect();
+        assert_eq!(threads, HashSet::from(["fizzbuzz-0".to_string()]));
+    }
+
+    #[test]
+    fn matches_global_pool() {
+        let pool = parallel::pool_builder().num_threads(3).build().unwrap();
+        let answers: Vec<String> = (1..1_000_000).fizzbuzz_in(&pool);
+        let expected: Vec<String> = (1..1_000_000).fizzbuzz().collect();
+        assert_eq!(answers, expected);
+    }
+
+    #[test]
+    fn write_on_dedicated_pool() {
+        let pool = single_thread();
+        let nums: Vec<Spy> = (1..50_000).map(Spy).collect();
+        let mut out = Vec::new();
+        parallel::with_threshold(0, || nums.write_fizzbuzz_in(&mut out, "\n", &pool)).unwrap();
+        let threads: HashSet<String> = FORMATTED_ON.lock().unwrap().drain(..).collect();
+        assert_eq!(threads, HashSet::from(["fizzbuzz-0".to_string()]));
+        let expected: Vec<String> = (1..50_000).fizzbuzz().collect();
+        assert_eq!(String::from_utf8(out).unwrap(), expected.join("\n"));
+    }
+}
@@ -0,0 +1,359 @@
+#![cfg(feature = "alloc")]
+
+use fizzbuzz::{
+    parse::{ParseError, ParseErrorKind, Position},
+    rules::{Combine, Fallback},
+    FizzBuzz, RuleSet,
+};
+
+fn answers(rules: &RuleSet<i32>) -> Vec<String> {
+    [1, 3, 5, 7, 15, 21, 105]
+        .iter()
+        .map(|n| n.fizzbuzz_with(rules).into())
+        .collect()
+}
+
+fn error_at(line: usize, column: usize, kind: ParseErrorKind) -> ParseError {
+    ParseError {
+        position: Position { line, column },
+        kind,
+    }
+}
+
+mod dsl {
+    use super::*;
+
+    #[test]
+    fn fizz_buzz_woof() {
+        let rules: RuleSet<i32> = "3=Fizz,5=Buzz,7=Woof;mode=concat".parse().unwrap();
+        assert_eq!(
+            answers(&rules),
+            vec![
+                "1",
+                "Fizz",
+                "Buzz",
+                "Woof",
+                "FizzBuzz",
+                "FizzWoof",
+                "FizzBuzzWoof"
+            ]
+        );
+    }
+
+    #[test]
+    fn default_mode_is_concat() {
+        let rules: RuleSet<i32> = "3=fizz,5=buzz".parse().unwrap();
+        assert_eq!(rules.combination(), &Combine::Concat);
+        assert_eq!(rules.fallback_answer(), &Fallback::Number);
+        let classic: Vec<String> = [1, 3, 5, 7, 15, 21, 105]
+            .iter()
+            .map(|n| n.fizzbuzz().into())
+            .collect();
+        assert_eq!(answers(&rules), classic);
+    }
+
+    #[test]
+    fn whitespace_and_newlines() {
+        let rules: RuleSet<i32> =
+            " 3 = Fizz ,\n 5 = Buzz\n ; mode = join ;\n separator = \" - \" ;"
+                .parse()
+                .unwrap();
+        assert_eq!(rules.combination(), &Combine::Join(" - ".into()));
+        assert_eq!(answers(&rules)[4], "Fizz - Buzz");
+    }
+
+    #[test]
+    fn modes() {
+        let first: RuleSet<i32> = "3=Fizz,5=Buzz;mode=first".parse().unwrap();
+        assert_eq!(first.combination(), &Combine::FirstMatch);
+        let priority: RuleSet<i32> = "3=Fizz,5=Buzz@2,7=Woof@1;mode=priority".parse().unwrap();
+        assert_eq!(
+            answers(&priority),
+            vec!["1", "Fizz", "Buzz", "Woof", "Buzz", "Woof", "Buzz"]
+        );
+    }
+
+    #[test]
+    fn quoted_at() {
+        let rules: RuleSet<i32> = "3=\"@Fizz\", 5=\"Bu@zz\"@2, 7=Woof@1; mode=priority"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            answers(&rules),
+            vec!["1", "@Fizz", "Bu@zz", "Woof", "Bu@zz", "Woof", "Bu@zz"]
+        );
+        let error = "3=\"Fi@zz\"@high".parse::<RuleSet<i32>>().unwrap_err();
+        assert_eq!(
+            error,
+            error_at(1, 11, ParseErrorKind::InvalidPriority("high".into()))
+        );
+    }
+
+    #[test]
+    fn fallback() {
+        let rules: RuleSet<i32> = "3=Fizz;fallback=nope".parse().unwrap();
+        assert_eq!(rules.fallback_answer(), &Fallback::Word("nope".into()));
+        assert_eq!(answers(&rules)[..2], ["nope", "Fizz"]);
+    }
+
+    #[test]
+    fn no_rules() {
+        let rules: RuleSet<i32> = "".parse().unwrap();
+        assert!(rules.is_empty());
+        let rules: RuleSet<i32> = ";fallback=nope".parse().unwrap();
+        assert!(rules.is_empty());
+    }
+
+    #[test]
+    fn floats() {
+        let rules: RuleSet<f64> = "2.5=half".parse().unwrap();
+        let answer: String = 7.5.fizzbuzz_with(&rules).into();
+        assert_eq!(answer, "half");
+    }
+
+    #[test]
+    fn zero_divisor() {
+        let error = "3=Fizz,\n 0=Zero".parse::<RuleSet<i32>>().unwrap_err();
+        assert_eq!(error, error_at(2, 2, ParseErrorKind::ZeroDivisor));
+        assert_eq!(
+            error.to_string(),
+            "divisor cannot be zero at line 2, column 2"
+        );
+    }
+
+    #[test]
+    fn duplicate_divisor() {
+        let error = "3=Fizz,5=Buzz,3=Again".parse::<RuleSet<i32>>().unwrap_err();
+        assert_eq!(
+            error,
+            error_at(1, 15, ParseErrorKind::DuplicateDivisor("3".into()))
+        );
+    }
+
+    #[test]
+    fn empty_word() {
+        let error = "3=Fizz,5= ".parse::<RuleSet<i32>>().unwrap_err();
+        assert_eq!(error, error_at(1, 10, ParseErrorKind::EmptyWord));
+        let error = "3=Fizz,5=\"\"".parse::<RuleSet<i32>>().unwrap_err();
+        assert_eq!(error, error_at(1, 11, ParseErrorKind::EmptyWord));
+    }
+
+    #[test]
+    fn invalid_divisor() {
+        let error = "3=Fizz,five=Buzz".parse::<RuleSet<i32>>().unwrap_err();
+        assert_eq!(
+            error,
+            error_at(1, 8, ParseErrorKind::InvalidDivisor("five".into()))
+        );
+        let error = "300=Fizz".parse::<RuleSet<u8>>().unwrap_err();
+        assert_eq!(
+            error,
+            error_at(1, 1, ParseErrorKind::InvalidDivisor("300".into()))
+        );
+    }
+
+    #[test]
+    fn invalid_priority() {
+        let error = "3=Fizz@high".parse::<RuleSet<i32>>().unwrap_err();
+        assert_eq!(
+            error,
+            error_at(1, 8, ParseErrorKind::InvalidPriority("high".into()))
+        );
+    }
+
+    #[test]
+    fn missing_equals() {
+        let error = "3=Fizz,5Buzz".parse::<RuleSet<i32>>().unwrap_err();
+        assert_eq!(
+            error,
+            error_at(1, 13, ParseErrorKind::Syntax("expected `=`".into()))
+        );
+    }
+
+    #[test]
+    fn missing_rule() {
+        let error = "3=Fizz,,5=Buzz".parse::<RuleSet<i32>>().unwrap_err();
+        assert_eq!(
+            error,
+            error_at(
+                1,
+                8,
+                ParseErrorKind::Syntax("expected a rule `divisor=word`".into())
+            )
+        );
+    }
+
+    #[test]
+    fn unknown_option() {
+        let error = "3=Fizz;\nmood=happy".parse::<RuleSet<i32>>().unwrap_err();
+        assert_eq!(
+            error,
+            error_at(2, 1, ParseErrorKind::UnknownOption("mood".into()))
+        );
+    }
+
+    #[test]
+    fn unknown_mode() {
+        let error = "3=Fizz;mode=shout".parse::<RuleSet<i32>>().unwrap_err();
+        assert_eq!(
+            error,
+            error_at(1, 13, ParseErrorKind::UnknownMode("shout".into()))
+        );
+    }
+
+    #[test]
+    fn separator() {
+        let error = "3=Fizz;mode=join".parse::<RuleSet<i32>>().unwrap_err();
+        assert_eq!(error, error_at(1, 13, ParseErrorKind::Separator));
+        let error = "3=Fizz;separator=-".parse::<RuleSet<i32>>().unwrap_err();
+        assert_eq!(error, error_at(1, 18, ParseErrorKind::Separator));
+    }
+}
+
+#[cfg(feature = "toml")]
+mod toml {
+    use super::*;
+
+    #[test]
+    fn fizz_buzz_woof() {
+        let rules: RuleSet<i32> = RuleSet::from_toml(
+            r#"
+mode = "join"
+separator = "-"
+fallback = "..."
+
+[[rules]]
+divisor = 3
+word = "Fizz"
+
+[[rules]]
+divisor = 5
+word = "Buzz"
+
+[[rules]]
+divisor = 7
+word = "Woof"
+priority = 1
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            answers(&rules),
+            vec![
+                "...",
+                "Fizz",
+                "Buzz",
+                "Woof",
+                "Fizz-Buzz",
+                "Fizz-Woof",
+                "Fizz-Buzz-Woof"
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicate_divisor() {
+        let error = RuleSet::<i32>::from_toml(
+            "rules = [\n  { divisor = 3, word = \"Fizz\" },\n  { divisor = 3, word = \"Again\" },\n]",
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            error_at(3, 15, ParseErrorKind::DuplicateDivisor("3".into()))
+        );
+    }
+
+    #[test]
+    fn zero_divisor_and_empty_word() {
+        let error =
+            RuleSet::<i32>::from_toml("[[rules]]\ndivisor = 0\nword = \"Zero\"").unwrap_err();
+        assert_eq!(error, error_at(2, 11, ParseErrorKind::ZeroDivisor));
+        let error = RuleSet::<i32>::from_toml("[[rules]]\ndivisor = 3\nword = \"\"").unwrap_err();
+        assert_eq!(error, error_at(3, 8, ParseErrorKind::EmptyWord));
+    }
+
+    #[test]
+    fn syntax_error() {
+        let error = RuleSet::<i32>::from_toml("[[rules]]\ndivisor = 3\nword = Fizz").unwrap_err();
+        assert_eq!(error.position, Position { line: 3, column: 8 });
+        assert!(matches!(error.kind, ParseErrorKind::Syntax(_)));
+    }
+
+    #[test]
+    fn unknown_field() {
+        let error = RuleSet::<i32>::from_toml("mood = \"happy\"").unwrap_err();
+        assert_eq!(error.position, Position { line: 1, column: 1 });
+        assert!(matches!(error.kind, ParseErrorKind::Syntax(_)));
+    }
+}
+
+#[cfg(feature = "json")]
+mod json {
+    use super::*;
+
+    #[test]
+    fn fizz_buzz_woof() {
+        let rules: RuleSet<i32> = RuleSet::from_json(
+            r#"{
+                "mode": "priority",
+                "rules": [
+                    {"divisor": 3, "word": "Fizz"},
+                    {"divisor": 5, "word": "Buzz", "priority": 2},
+                    {"divisor": "7", "word": "Woof", "priority": 1}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            answers(&rules),
+            vec!["1", "Fizz", "Buzz", "Woof", "Buzz", "Woof", "Buzz"]
+        );
+    }
+
+    #[test]
+    fn duplicate_divisor() {
+        let error = RuleSet::<i32>::from_json(
+            "{\"rules\": [\n  {\"divisor\": 3, \"word\": \"Fizz\"},\n  {\"divisor\": 3, \"word\": \"Again\"}\n]}",
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            error_at(3, 15, ParseErrorKind::DuplicateDivisor("3".into()))
+        );
+    }
+
+    #[test]
+    fn empty_word() {
+        let error = RuleSet::<i32>::from_json("{\"rules\": [{\"divisor\": 3, \"word\": \"\"}]}")
+            .unwrap_err();
+        assert_eq!(error, error_at(1, 35, ParseErrorKind::EmptyWord));
+    }
+
+    #[test]
+    fn syntax_error() {
+        let error =
+            RuleSet::<i32>::from_json("{\"rules\": [\n  {\"divisor\": 3,}\n]}").unwrap_err();
+        assert_eq!(
+            error.position,
+            Position {
+                line: 2,
+                column: 17
+            }
+        );
+        assert!(matches!(error.kind, ParseErrorKind::Syntax(_)));
+    }
+
+    #[test]
+    fn word_must_be_a_string() {
+        let error =
+            RuleSet::<i32>::from_json("{\"rules\": [{\"divisor\": 3, \"word\": 3}]}").unwrap_err();
+        assert_eq!(
+            error.position,
+            Position {
+                line: 1,
+                column: 35
+            }
+        );
+        assert!(matches!(error.kind, ParseErrorKind::Syntax(_)));
+    }
+}
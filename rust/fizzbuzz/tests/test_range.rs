@@ -0,0 +1,511 @@
+#![cfg(feature = "rayon")]
+
+use fizzbuzz::range::RangeFizzBuzz;
+use fizzbuzz::rules::{Combine, Fallback, Prime};
+use fizzbuzz::{FizzBuzz, FizzBuzzAnswer, MultiFizzBuzz, RuleSet};
+use rayon::iter::ParallelIterator;
+
+mod common;
+use common::{expected, expected_lines, expected_with};
+
+mod classic {
+    use super::*;
+
+    #[test]
+    fn matches_fizzbuzz() {
+        let wheel: Vec<FizzBuzzAnswer<_>> = (-100..100_i32).fizzbuzz_wheel().collect();
+        let expected: Vec<FizzBuzzAnswer<_>> = (-100..100_i32).fizzbuzz().collect();
+        assert_eq!(wheel, expected)
+    }
+
+    #[test]
+    fn big_range_is_well_ordered() {
+        let wheel: Vec<String> = (7..1_000_007_u32).fizzbuzz_wheel().collect();
+        let expected: Vec<String> = (7..1_000_007_u32).fizzbuzz().collect();
+        assert_eq!(wheel, expected)
+    }
+
+    #[test]
+    fn shorter_than_period() {
+        let wheel: Vec<String> = (1..6_u8).fizzbuzz_wheel().collect();
+        assert_eq!(wheel, vec!["1", "2", "fizz", "4", "buzz"])
+    }
+
+    #[test]
+    fn empty() {
+        let wheel: Vec<String> = (5..5_i8).fizzbuzz_wheel().collect();
+        assert!(wheel.is_empty());
+        #[allow(clippy::reversed_empty_ranges)]
+        let wheel: Vec<String> = (5..=4_i8).fizzbuzz_wheel().collect();
+        assert!(wheel.is_empty());
+    }
+
+    #[test]
+    fn whole_of_type() {
+        let wheel: Vec<String> = (i8::MIN..=i8::MAX).fizzbuzz_wheel().collect();
+        let expected: Vec<String> = (i8::MIN..=i8::MAX).map(|n| n.fizzbuzz().into()).collect();
+        assert_eq!(wheel, expected)
+    }
+
+    #[test]
+    fn wide_types() {
+        let start = u64::MAX - 100;
+        let wheel: Vec<String> = (start..=u64::MAX).fizzbuzz_wheel().collect();
+        let expected: Vec<String> = (start..=u64::MAX).map(|n| n.fizzbuzz().into()).collect();
+        assert_eq!(wheel, expected);
+
+        let start = i128::MIN;
+        let wheel: Vec<String> = (start..start + 100).fizzbuzz_wheel().collect();
+        let expected: Vec<String> = (start..start + 100).map(|n| n.fizzbuzz().into()).collect();
+        assert_eq!(wheel, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "usize::MAX")]
+    fn too_long() {
+        let _ = (0..u128::MAX).fizzbuzz_wheel::<String>();
+    }
+}
+
+mod rule_sets {
+    use super::*;
+
+    #[test]
+    fn periodic() {
+        let rules = RuleSet::default().divisor(7, "woof");
+        let wheel: Vec<String> = (-500..500_i64).fizzbuzz_wheel_with(&rules).collect();
+        assert_eq!(wheel, expected_with(-500..500, &rules))
+    }
+
+    #[test]
+    fn negative_divisor() {
+        let rules = RuleSet::new().divisor(-4, "four").divisor(6, "six");
+        let wheel: Vec<String> = (-50..50_i64).fizzbuzz_wheel_with(&rules).collect();
+        assert_eq!(wheel, expected_with(-50..50, &rules))
+    }
+
+    #[test]
+    fn combinations_and_fallback() {
+        let rules = RuleSet::default()
+            .divisor(7, "woof")
+            .combine(Combine::Join("-".into()))
+            .fallback(Fallback::Word("...".into()));
+        let wheel: Vec<String> = (0..300_i64).fizzbuzz_wheel_with(&rules).collect();
+        assert_eq!(wheel, expected_with(0..300, &rules))
+    }
+
+    #[test]
+    fn not_periodic() {
+        let rules = RuleSet::default().rule(Prime, "prime");
+        let wheel: Vec<String> = (0..300_i64).fizzbuzz_wheel_with(&rules).collect();
+        assert_eq!(wheel, expected_with(0..300, &rules))
+    }
+
+    #[test]
+    fn period_too_large() {
+        let rules = RuleSet::new()
+            .divisor(1_000_003, "big")
+            .divisor(1_000_033, "bigger");
+        let range = 1_000_000..1_100_000_i64;
+        let wheel: Vec<String> = range.clone().fizzbuzz_wheel_with(&rules).collect();
+        assert_eq!(wheel, expected_with(range, &rules))
+    }
+
+    #[test]
+    fn big_range_is_well_ordered() {
+        let rules = RuleSet::default().divisor(7, "woof").divisor(11, "bang");
+        let range = -3..1_000_000_i64;
+        let wheel: Vec<String> = range.clone().fizzbuzz_wheel_with(&rules).collect();
+        assert_eq!(wheel, expected_with(range, &rules))
+    }
+}
+
+mod sieve {
+    use super::*;
+
+    #[test]
+    fn large_divisors() {
+        let rules = RuleSet::new()
+            .divisor(1_009, "big")
+            .divisor(-1_013, "bigger")
+            .divisor(2, "even");
+        let range = -2_000_000..2_000_000_i64;
+        let sieved: Vec<String> = range.clone().fizzbuzz_sieve_with(&rules).collect();
+        assert_eq!(sieved, expected_with(range, &rules))
+    }
+
+    #[test]
+    fn combinations_and_fallback() {
+        let rules = RuleSet::default()
+            .divisor(7, "woof")
+            .combine(Combine::HighestPriority)
+            .fallback(Fallback::Word("...".into()));
+        let sieved: Vec<String> = (0..=300_u16).fizzbuzz_sieve_with(&rules).collect();
+        assert_eq!(sieved, expected_with(0..=300, &rules))
+    }
+
+    #[test]
+    fn extremes_of_type() {
+        let rules = RuleSet::new().divisor(-3, "three").divisor(i8::MIN, "min");
+        let sieved: Vec<String> = (i8::MIN..=i8::MAX).fizzbuzz_sieve_with(&rules).collect();
+        assert_eq!(sieved, expected_with(i8::MIN..=i8::MAX, &rules));
+
+        let rules = RuleSet::new().divisor(u128::MAX, "max").divisor(3, "fizz");
+        let range = u128::MAX - 10..=u128::MAX;
+        let sieved: Vec<String> = range.clone().fizzbuzz_sieve_with(&rules).collect();
+        assert_eq!(sieved, expected_with(range, &rules));
+    }
+
+    #[test]
+    fn not_divisors() {
+        let rules = RuleSet::default().rule(Prime, "prime");
+        let sieved: Vec<String> = (0..300_i64).fizzbuzz_sieve_with(&rules).collect();
+        assert_eq!(sieved, expected_with(0..300, &rules))
+    }
+}
+
+mod lines {
+    use super::*;
+
+    fn written(range: impl RangeFizzBuzz) -> Vec<u8> {
+        let mut out = Vec::new();
+        range.write_fizzbuzz_lines(&mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn one_to_fifteen() {
+        assert_eq!(
+            written(1..=15_u32),
+            b"1\n2\nfizz\n4\nbuzz\nfizz\n7\n8\nfizz\nbuzz\n11\nfizz\n13\n14\nfizzbuzz\n"
+        );
+    }
+
+    #[test]
+    fn unaligned_starts_and_ends() {
+        for start in 0..16 {
+            for end in start..start + 40 {
+                assert_eq!(
+                    written(start..end),
+                    expected_lines(start..end),
+                    "{start}..{end}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn negative_numbers() {
+        assert_eq!(written(-100..100_i32), expected_lines(-100..100_i32));
+    }
+
+    #[test]
+    fn digits_carry() {
+        let range = 999_990..1_000_010_u64;
+        assert_eq!(written(range.clone()), expected_lines(range));
+        let range = 9_999_999_999_999_990_000..10_000_000_000_000_010_000_u64;
+        assert_eq!(written(range.clone()), expected_lines(range));
+    }
+
+    #[test]
+    fn extremes_of_type() {
+        assert_eq!(
+            written(i8::MIN..=i8::MAX),
+            expected_lines(i8::MIN..=i8::MAX)
+        );
+        assert_eq!(written(0..=u8::MAX), expected_lines(0..=u8::MAX));
+        let range = u128::MAX - 100..=u128::MAX;
+        assert_eq!(written(range.clone()), expected_lines(range));
+        let range = i128::MIN..i128::MIN + 100;
+        assert_eq!(written(range.clone()), expected_lines(range));
+    }
+
+    #[test]
+    fn big_range_is_well_ordered() {
+        let range = -7..5_000_000_i64;
+        assert_eq!(written(range.clone()), expected_lines(range));
+    }
+
+    #[test]
+    fn blocks() {
+        let range = 3..2_000_000_u32;
+        let blocks: Vec<Vec<u8>> = range.clone().fizzbuzz_lines().collect();
+        assert!(blocks.len() > 1);
+        assert_eq!(blocks.concat(), expected_lines(range));
+    }
+
+    #[test]
+    fn empty() {
+        assert!(written(5..5_u16).is_empty());
+        let blocks: Vec<Vec<u8>> = (5..5_u16).fizzbuzz_lines().collect();
+        assert_eq!(blocks.concat(), b"");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn splice_into_pipe() {
+        use std::io::Read;
+
+        let range = 1..3_000_000_u64;
+        let (mut reader, mut writer) = std::io::pipe().unwrap();
+        let read = std::thread::spawn(move || {
+            let mut out = Vec::new();
+            reader.read_to_end(&mut out).unwrap();
+            out
+        });
+        range.clone().splice_fizzbuzz_lines(&mut writer).unwrap();
+        drop(writer);
+        assert_eq!(read.join().unwrap(), expected_lines(range));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn splice_restores_pipe_size() {
+        use std::io::Read;
+        use std::os::fd::AsRawFd;
+
+        const SIZE: libc::c_int = 1 << 20;
+        let range = 1..3_000_000_u64;
+        let (mut reader, mut writer) = std::io::pipe().unwrap();
+        let fd = writer.as_raw_fd();
+        // SAFETY: `fcntl` with `F_SETPIPE_SZ` and `F_GETPIPE_SZ` only configures the open pipe
+        if unsafe { libc::fcntl(fd, libc::F_SETPIPE_SZ, SIZE) } != SIZE {
+            return; // the limit for unprivileged users is lower
+        }
+        let read = std::thread::spawn(move || {
+            let mut out = Vec::new();
+            reader.read_to_end(&mut out).unwrap();
+            out
+        });
+        range.clone().splice_fizzbuzz_lines(&mut writer).unwrap();
+        assert_eq!(unsafe { libc::fcntl(fd, libc::F_GETPIPE_SZ) }, SIZE);
+        drop(writer);
+        assert_eq!(read.join().unwrap(), expected_lines(range));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn splice_into_file() {
+        let path = std::env::temp_dir().join(format!("fizzbuzz-lines-{}", std::process::id()));
+        let mut file = std::fs::File::create(&path).unwrap();
+        let range = 1..1_000_000_u32;
+        range.clone().splice_fizzbuzz_lines(&mut file).unwrap();
+        drop(file);
+        let written = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(written, expected_lines(range));
+    }
+}
+
+mod indexed {
+    use super::*;
+    use fizzbuzz::range::ParRange;
+    use rayon::iter::IndexedParallelIterator;
+
+    #[test]
+    fn inclusive() {
+        let answers: Vec<String> = ParRange::from(-20..=20_i32).fizzbuzz().collect();
+        assert_eq!(answers, expected(-20..=20_i32));
+    }
+
+    #[test]
+    fn wide_types() {
+        let answers: Vec<String> = ParRange::from(u64::MAX - 100..=u64::MAX)
+            .fizzbuzz()
+            .collect();
+        assert_eq!(answers, expected(u64::MAX - 100..=u64::MAX));
+        let answers: Vec<String> = ParRange::from(i64::MIN..i64::MIN + 100)
+            .fizzbuzz()
+            .collect();
+        assert_eq!(answers, expected(i64::MIN..i64::MIN + 100));
+        let answers: Vec<String> = ParRange::from(i128::MAX - 50..=i128::MAX)
+            .fizzbuzz()
+            .collect();
+        assert_eq!(answers, expected(i128::MAX - 50..=i128::MAX));
+        let answers: Vec<String> = ParRange::from(u128::MAX - 50..u128::MAX)
+            .fizzbuzz()
+            .collect();
+        assert_eq!(answers, expected(u128::MAX - 50..u128::MAX));
+    }
+
+    #[test]
+    fn big_range_is_well_ordered() {
+        let answers: Vec<FizzBuzzAnswer<u64>> =
+            ParRange::from(7..=1_000_007_u64).fizzbuzz().collect();
+        let expected: Vec<FizzBuzzAnswer<u64>> =
+            (7..=1_000_007_u64).map(|n| n.fizzbuzz()).collect();
+        assert_eq!(answers, expected);
+    }
+
+    #[test]
+    fn stepped_and_descending() {
+        let range = ParRange::from(-100..=100_i64);
+        let answers: Vec<String> = range.step_by(7).fizzbuzz().collect();
+        assert_eq!(answers, expected((-100..=100_i64).step_by(7)));
+        let answers: Vec<String> = range.rev().fizzbuzz().collect();
+        assert_eq!(answers, expected((-100..=100_i64).rev()));
+        let answers: Vec<String> = range.rev().step_by(7).fizzbuzz().collect();
+        assert_eq!(answers, expected((-100..=100_i64).rev().step_by(7)));
+        let answers: Vec<String> = range.step_by(7).rev().fizzbuzz().collect();
+        assert_eq!(answers, expected((-100..=96_i64).rev().step_by(7)));
+    }
+
+    #[test]
+    fn whole_of_type() {
+        let whole = ParRange::from(i8::MIN..=i8::MAX);
+        assert_eq!(
+            whole.fizzbuzz::<String>().collect::<Vec<_>>(),
+            expected(i8::MIN..=i8::MAX)
+        );
+        let answers: Vec<String> = whole.rev().step_by(255).fizzbuzz().collect();
+        assert_eq!(answers, vec!["127", "-128"]);
+        let answers: Vec<String> = ParRange::from(0..=u8::MAX)
+            .step_by(300)
+            .fizzbuzz()
+            .collect();
+        assert_eq!(answers, vec!["fizzbuzz"]);
+    }
+
+    #[test]
+    fn empty() {
+        #[allow(clippy::reversed_empty_ranges)]
+        for range in [ParRange::from(5..5_u64), ParRange::from(5..=4_u64)] {
+            assert_eq!(range.len(), 0);
+            assert_eq!(range.rev().step_by(3).fizzbuzz::<String>().count(), 0);
+            assert_eq!(range.subranges(10).count(), 0);
+        }
+    }
+
+    #[test]
+    fn longer_than_usize() {
+        let whole = ParRange::from(0..=u128::MAX);
+        assert_eq!(whole.checked_len(), None);
+        let odd = whole.step_by(2).rev();
+        let answers: Vec<String> = odd.subranges(5).next().unwrap().fizzbuzz().collect();
+        assert_eq!(
+            answers,
+            expected((u128::MAX - 9..=u128::MAX - 1).rev().step_by(2))
+        );
+        let stepped = whole.step_by(1 << 63).step_by(1 << 62);
+        assert_eq!(stepped.checked_len(), Some(8));
+    }
+
+    #[test]
+    fn subranges() {
+        let range = ParRange::from(1..=u64::MAX);
+        let mut subranges = range.subranges(1_000);
+        let first: Vec<String> = subranges.next().unwrap().fizzbuzz().collect();
+        assert_eq!(first, expected(1..=1_000_u64));
+        let later: Vec<String> = subranges.nth(999).unwrap().fizzbuzz().collect();
+        assert_eq!(later, expected(1_000_001..=1_001_000_u64));
+
+        let lens: Vec<usize> = ParRange::from(0..25_u8)
+            .subranges(10)
+            .map(|subrange| subrange.len())
+            .collect();
+        assert_eq!(lens, vec![10, 10, 5]);
+        let lens: Vec<usize> = ParRange::from(0..=u128::MAX)
+            .subranges(usize::MAX)
+            .take(2)
+            .map(|subrange| subrange.len())
+            .collect();
+        assert_eq!(lens, vec![usize::MAX, usize::MAX]);
+    }
+
+    #[test]
+    fn split_at() {
+        let (head, tail) = ParRange::from(1..=10_i32).rev().split_at(4);
+        assert_eq!(
+            head.fizzbuzz::<String>().collect::<Vec<_>>(),
+            expected((7..=10).rev())
+        );
+        assert_eq!(
+            tail.fizzbuzz::<String>().collect::<Vec<_>>(),
+            expected((1..=6).rev())
+        );
+        let (all, none) = tail.split_at(6);
+        assert_eq!((all, none.len()), (tail, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "usize::MAX")]
+    fn too_long() {
+        let _ = ParRange::from(0..=u64::MAX).fizzbuzz::<String>();
+    }
+}
+
+mod slice {
+    use super::*;
+    use fizzbuzz::range::{SliceSpec, ZeroStepError};
+
+    /// The numbers in `range(start, stop, step)`, stepping past the bounds of `i8` with `i16`s
+    fn python(start: Option<i8>, stop: Option<i8>, step: Option<i8>) -> Vec<i8> {
+        let step = i16::from(step.unwrap_or(1));
+        let (start, stop) = if step > 0 {
+            (start.unwrap_or(i8::MIN), stop.map_or(128, i16::from))
+        } else {
+            (start.unwrap_or(i8::MAX), stop.map_or(-129, i16::from))
+        };
+        let mut n = i16::from(start);
+        let mut numbers = vec![];
+        while (step > 0 && n < stop) || (step < 0 && n > stop) {
+            numbers.push(n as i8);
+            n += step;
+        }
+        numbers
+    }
+
+    #[test]
+    fn matches_python() {
+        let bounds = [
+            None,
+            Some(i8::MIN),
+            Some(-100),
+            Some(-1),
+            Some(0),
+            Some(3),
+            Some(i8::MAX),
+        ];
+        let steps = [
+            None,
+            Some(i8::MIN),
+            Some(-7),
+            Some(-1),
+            Some(1),
+            Some(2),
+            Some(i8::MAX),
+        ];
+        for start in bounds {
+            for stop in bounds {
+                for step in steps {
+                    let slice = SliceSpec::new(start, stop, step).unwrap();
+                    let answers: Vec<String> = slice.fizzbuzz().collect();
+                    let expected: Vec<String> = python(start, stop, step)
+                        .into_iter()
+                        .map(|n| n.fizzbuzz().into())
+                        .collect();
+                    assert_eq!(answers, expected, "slice({start:?}, {stop:?}, {step:?})");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn negative_step_at_bounds() {
+        let slice = SliceSpec::new(Some(isize::MIN + 9), Some(isize::MIN), Some(-3)).unwrap();
+        let numbers: Vec<isize> = slice.range().collect();
+        assert_eq!(
+            numbers,
+            vec![isize::MIN + 9, isize::MIN + 6, isize::MIN + 3]
+        );
+        let slice = SliceSpec::new(Some(i64::MAX), None, Some(i64::MIN)).unwrap();
+        assert_eq!(slice.range().collect::<Vec<_>>(), vec![i64::MAX, -1]);
+    }
+
+    #[test]
+    fn zero_step() {
+        let error = SliceSpec::new(Some(1_i64), Some(5), Some(0)).unwrap_err();
+        assert_eq!(error, ZeroStepError);
+        assert_eq!(error.to_string(), "slice step cannot be zero");
+    }
+}
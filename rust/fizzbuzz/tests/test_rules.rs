@@ -0,0 +1,332 @@
+#![cfg(feature = "rayon")]
+
+use fizzbuzz::{FizzBuzz, FizzBuzzAnswer, MultiFizzBuzz, RuleSet};
+use rayon::iter::ParallelIterator;
+
+mod default_rules {
+    use super::*;
+
+    #[test]
+    fn matches_classic_game() {
+        let rules = RuleSet::default();
+        for i in -50..50_i32 {
+            assert_eq!(i.fizzbuzz_with(&rules), i.fizzbuzz(), "for {i}")
+        }
+    }
+
+    #[test]
+    fn float() {
+        let rules = RuleSet::default();
+        let answer: String = 3.2_f64.fizzbuzz_with(&rules).into();
+        assert_eq!(answer, "3.2");
+        let answer: String = 15_f64.fizzbuzz_with(&rules).into();
+        assert_eq!(answer, "fizzbuzz");
+    }
+
+    #[test]
+    fn rules_are_ordered() {
+        let rules: RuleSet<i32> = RuleSet::default();
+        let words: Vec<_> = rules.words().collect();
+        assert_eq!(words, vec!["fizz", "buzz"]);
+    }
+}
+
+mod custom_rules {
+    use super::*;
+
+    #[test]
+    fn fizz_buzz_woof() {
+        let rules = RuleSet::new()
+            .divisor(3, "Fizz")
+            .divisor(5, "Buzz")
+            .divisor(7, "Woof");
+        let answers: Vec<String> = [1, 3, 7, 21, 35, 105]
+            .iter()
+            .map(|n| n.fizzbuzz_with(&rules).into())
+            .collect();
+        assert_eq!(
+            answers,
+            vec!["1", "Fizz", "Woof", "FizzWoof", "BuzzWoof", "FizzBuzzWoof"]
+        )
+    }
+
+    #[test]
+    fn order_of_rules_is_order_of_words() {
+        let rules = RuleSet::new().divisor(5, "buzz").divisor(3, "fizz");
+        let answer: String = 15.fizzbuzz_with(&rules).into();
+        assert_eq!(answer, "buzzfizz")
+    }
+
+    #[test]
+    fn single_match_is_a_word() {
+        let rules = RuleSet::new().divisor(7, "woof");
+        assert_eq!(
+            14.fizzbuzz_with(&rules),
+            FizzBuzzAnswer::Word("woof".into())
+        );
+        assert_eq!(15.fizzbuzz_with(&rules), FizzBuzzAnswer::Number(15));
+    }
+
+    #[test]
+    fn empty_rules() {
+        let rules = RuleSet::new();
+        assert!(rules.is_empty());
+        let answer: String = 15.fizzbuzz_with(&rules).into();
+        assert_eq!(answer, "15")
+    }
+}
+
+mod builtin_rules {
+    use fizzbuzz::rules::{ContainsDigit, Divisible, PerfectSquare, Prime, Rule};
+
+    use super::*;
+
+    #[test]
+    fn divisible() {
+        let seven = Divisible::new(7_u64);
+        assert!(seven.applies(&0));
+        assert!(seven.applies(&49));
+        assert!(!seven.applies(&50));
+        assert_eq!(seven.divisor(), &7);
+    }
+
+    #[test]
+    fn divisible_matches_remainder() {
+        for divisor in [-12_i64, -7, 1, 2, 3, 10, 96, 1_000_000_007] {
+            let rule = Divisible::new(divisor);
+            for n in (-1_000..1_000).chain(i64::MAX - 1_000..=i64::MAX) {
+                assert_eq!(rule.applies(&n), n % divisor == 0, "{n} / {divisor}");
+            }
+        }
+        let rule = Divisible::new(u128::MAX / 3);
+        assert!(rule.applies(&(u128::MAX / 3 * 2)));
+        assert!(!rule.applies(&(u128::MAX / 3 * 2 + 1)));
+        let rule = Divisible::new(1.5_f64);
+        assert!(rule.applies(&4.5));
+        assert!(!rule.applies(&5.0));
+    }
+
+    #[test]
+    fn divisible_min_by_minus_one() {
+        let rule = Divisible::new(-1_i32);
+        assert!(rule.applies(&i32::MIN));
+        let rules = RuleSet::new().divisor(-1_i64, "x").divisor(i64::MIN, "min");
+        let answer: String = i64::MIN.fizzbuzz_with(&rules).into();
+        assert_eq!(answer, "xmin");
+    }
+
+    #[test]
+    fn ranges_from_min_by_minus_one() {
+        use fizzbuzz::range::RangeFizzBuzz;
+
+        let rules = RuleSet::new().divisor(-1_i32, "x");
+        let expected = vec!["x"; 3];
+        let answers: Vec<String> = (i32::MIN..i32::MIN + 3).fizzbuzz_with(&rules).collect();
+        assert_eq!(answers, expected);
+        let answers: Vec<String> = (i32::MIN..i32::MIN + 3)
+            .fizzbuzz_wheel_with(&rules)
+            .collect();
+        assert_eq!(answers, expected);
+        let answers: Vec<String> = (i32::MIN..i32::MIN + 3)
+            .fizzbuzz_sieve_with(&rules)
+            .collect();
+        assert_eq!(answers, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "divisor cannot be zero")]
+    fn divisible_by_zero() {
+        Divisible::new(0_u32);
+    }
+
+    #[test]
+    #[should_panic(expected = "divisor cannot be zero")]
+    fn rule_set_divisor_zero() {
+        let _ = RuleSet::default().divisor(0.0_f64, "never");
+    }
+
+    #[test]
+    fn contains_digit() {
+        let three = ContainsDigit::new(3);
+        assert!(three.applies(&13));
+        assert!(three.applies(&-301));
+        assert!(!three.applies(&12));
+        assert!(three.applies(&1.3));
+    }
+
+    #[test]
+    #[should_panic]
+    fn contains_digit_not_a_digit() {
+        ContainsDigit::new(10);
+    }
+
+    #[test]
+    fn prime() {
+        let primes: Vec<u8> = (0..=255).filter(|n| Prime.applies(n)).collect();
+        assert_eq!(primes.len(), 54);
+        assert_eq!(primes[..10], [2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert_eq!(primes.last(), Some(&251));
+        assert!(!Prime.applies(&-7_i32));
+        assert!(Prime.applies(&2_147_483_647_i32));
+        assert!(Prime.applies(&1_000_000_007_u64));
+        assert!(!Prime.applies(&(1_000_000_007_u64 * 1_000_000_009)));
+    }
+
+    #[test]
+    fn perfect_square() {
+        let squares: Vec<i16> = (-100..=100).filter(|n| PerfectSquare.applies(n)).collect();
+        assert_eq!(squares, vec![0, 1, 4, 9, 16, 25, 36, 49, 64, 81, 100]);
+        assert!(PerfectSquare.applies(&(u128::from(u64::MAX) * u128::from(u64::MAX))));
+        assert!(!PerfectSquare.applies(&u128::MAX));
+    }
+
+    #[test]
+    fn closure() {
+        let rules = RuleSet::new().rule(|n: &i32| *n < 0, "negative");
+        let answer: String = (-4).fizzbuzz_with(&rules).into();
+        assert_eq!(answer, "negative");
+    }
+
+    #[test]
+    fn mixed() {
+        let rules = RuleSet::new()
+            .divisor(3, "fizz")
+            .rule(ContainsDigit::new(3), "fizz")
+            .rule(Prime, "prime")
+            .rule(PerfectSquare, "square");
+        let answers: Vec<String> = vec![3, 4, 6, 13, 16, 20].fizzbuzz_with(&rules).collect();
+        assert_eq!(
+            answers,
+            vec![
+                "fizzfizzprime",
+                "square",
+                "fizz",
+                "fizzprime",
+                "square",
+                "20"
+            ]
+        );
+    }
+}
+
+mod combinations {
+    use fizzbuzz::rules::{Combine, Divisible, Fallback};
+
+    use super::*;
+
+    fn answers(rules: &RuleSet<i32>) -> Vec<String> {
+        vec![1, 3, 5, 7, 15, 21, 105].fizzbuzz_with(rules).collect()
+    }
+
+    fn fizz_buzz_woof() -> RuleSet<i32> {
+        RuleSet::new()
+            .divisor(3, "Fizz")
+            .rule_with_priority(Divisible::new(5), "Buzz", 2)
+            .rule_with_priority(Divisible::new(7), "Woof", 1)
+    }
+
+    #[test]
+    fn concat_is_default() {
+        let rules = fizz_buzz_woof();
+        assert_eq!(rules.combination(), &Combine::Concat);
+        assert_eq!(rules.fallback_answer(), &Fallback::Number);
+        assert_eq!(
+            answers(&rules),
+            vec![
+                "1",
+                "Fizz",
+                "Buzz",
+                "Woof",
+                "FizzBuzz",
+                "FizzWoof",
+                "FizzBuzzWoof"
+            ]
+        )
+    }
+
+    #[test]
+    fn join() {
+        let rules = fizz_buzz_woof().combine(Combine::Join("-".into()));
+        assert_eq!(
+            answers(&rules),
+            vec![
+                "1",
+                "Fizz",
+                "Buzz",
+                "Woof",
+                "Fizz-Buzz",
+                "Fizz-Woof",
+                "Fizz-Buzz-Woof"
+            ]
+        )
+    }
+
+    #[test]
+    fn first_match() {
+        let rules = fizz_buzz_woof().combine(Combine::FirstMatch);
+        assert_eq!(
+            answers(&rules),
+            vec!["1", "Fizz", "Buzz", "Woof", "Fizz", "Fizz", "Fizz"]
+        )
+    }
+
+    #[test]
+    fn highest_priority() {
+        let rules = fizz_buzz_woof().combine(Combine::HighestPriority);
+        assert_eq!(
+            answers(&rules),
+            vec!["1", "Fizz", "Buzz", "Woof", "Buzz", "Woof", "Buzz"]
+        )
+    }
+
+    #[test]
+    fn highest_priority_tie_is_first_match() {
+        let rules = RuleSet::default().combine(Combine::HighestPriority);
+        let answer: String = 15.fizzbuzz_with(&rules).into();
+        assert_eq!(answer, "fizz")
+    }
+
+    #[test]
+    fn fallback_word() {
+        let rules = fizz_buzz_woof().fallback(Fallback::Word("nope".into()));
+        assert_eq!(1.fizzbuzz_with(&rules), FizzBuzzAnswer::Word("nope".into()));
+        assert_eq!(answers(&rules)[1..], answers(&fizz_buzz_woof())[1..])
+    }
+
+    #[test]
+    fn empty_rules_with_fallback() {
+        let rules = RuleSet::new().fallback(Fallback::Word("nope".into()));
+        let answer: String = 15.fizzbuzz_with(&rules).into();
+        assert_eq!(answer, "nope")
+    }
+}
+
+mod multi {
+    use rayon::iter::{IndexedParallelIterator, IntoParallelIterator};
+
+    use super::*;
+
+    #[test]
+    fn vec() {
+        let rules = RuleSet::default().divisor(7, "woof");
+        let answers: Vec<String> = vec![1, 7, 15, 21].fizzbuzz_with(&rules).collect();
+        assert_eq!(answers, vec!["1", "woof", "fizzbuzz", "fizzwoof"])
+    }
+
+    #[test]
+    fn stepped_range() {
+        let rules = RuleSet::new().divisor(2, "even");
+        let input = (0..10).into_par_iter().step_by(3);
+        let answers: Vec<String> = input.fizzbuzz_with(&rules).collect();
+        assert_eq!(answers, vec!["even", "3", "even", "9"])
+    }
+
+    #[test]
+    fn big_range_is_well_ordered() {
+        let rules = RuleSet::default().divisor(7, "woof");
+        let output: Vec<FizzBuzzAnswer<_>> = (1..1_000_000).fizzbuzz_with(&rules).collect();
+        let expected: Vec<FizzBuzzAnswer<_>> =
+            (1..1_000_000).map(|n| n.fizzbuzz_with(&rules)).collect();
+        assert_eq!(output, expected)
+    }
+}
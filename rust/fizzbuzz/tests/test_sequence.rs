@@ -0,0 +1,174 @@
+use fizzbuzz::{FizzBuzz, FizzBuzzAnswer, Sequence};
+
+/// The first `len` items of a sequence, evaluated number by number
+fn expected<Num: FizzBuzz + Copy>(
+    start: Num,
+    step: impl Fn(Num) -> Num,
+    len: usize,
+) -> Vec<(Num, FizzBuzzAnswer<Num>)> {
+    let mut n = start;
+    (0..len)
+        .map(|i| {
+            if i > 0 {
+                n = step(n);
+            }
+            (n, n.fizzbuzz())
+        })
+        .collect()
+}
+
+#[test]
+fn from_one() {
+    let answers: Vec<_> = Sequence::new(1_u32).take(100).collect();
+    assert_eq!(answers, expected(1, |n| n + 1, 100));
+}
+
+#[test]
+fn any_start_and_step() {
+    for start in [-47_i64, -1, 0, 1, 14, 1_000_003] {
+        for step in [-16, -15, -7, -3, -1, 1, 2, 5, 6, 15, 29] {
+            let answers: Vec<_> = Sequence::with_step(start, step).take(60).collect();
+            assert_eq!(
+                answers,
+                expected(start, |n| n + step, 60),
+                "from {start} in steps of {step}"
+            );
+        }
+    }
+}
+
+#[test]
+fn zero_step() {
+    let answers: Vec<_> = Sequence::with_step(9_u8, 0).take(3).collect();
+    assert_eq!(answers, vec![(9, FizzBuzzAnswer::Fizz); 3]);
+}
+
+#[test]
+fn nth_matches_next() {
+    let sequence = Sequence::with_step(-1_000_i32, 7);
+    for k in [0, 1, 14, 15, 16, 1_000, 123_457] {
+        let mut skipped = sequence.clone().skip(k);
+        assert_eq!(sequence.clone().nth(k), skipped.next(), "{k}");
+        let n = -1_000 + 7 * k as i32;
+        assert_eq!(sequence.clone().nth(k), Some((n, n.fizzbuzz())));
+    }
+}
+
+#[test]
+fn skips_far_ahead() {
+    let mut sequence = Sequence::new(0_u128);
+    let n = 1_u128 << 64;
+    assert_eq!(sequence.nth(usize::MAX), Some((n - 1, (n - 1).fizzbuzz())));
+    assert_eq!(sequence.next(), Some((n, n.fizzbuzz())));
+}
+
+#[test]
+fn clones_are_independent() {
+    let mut sequence = Sequence::new(1_usize);
+    sequence.nth(13);
+    let mut clone = sequence.clone();
+    assert_eq!(sequence.next(), Some((15, FizzBuzzAnswer::Fizzbuzz)));
+    assert_eq!(sequence.next(), Some((16, FizzBuzzAnswer::Number(16))));
+    assert_eq!(clone.next(), Some((15, FizzBuzzAnswer::Fizzbuzz)));
+}
+
+#[test]
+fn ends_at_bounds_of_type() {
+    let answers: Vec<_> = Sequence::new(250_u8).collect();
+    assert_eq!(answers, expected(250, |n| n + 1, 6));
+    let answers: Vec<_> = Sequence::with_step(-120_i8, -3).collect();
+    assert_eq!(answers, expected(-120, |n| n - 3, 3));
+    assert_eq!(Sequence::new(0_i8).nth(128), None);
+    assert_eq!(
+        Sequence::with_step(127_i8, -1).nth(255),
+        Some((-128, (-128_i8).fizzbuzz()))
+    );
+    assert_eq!(Sequence::new(u128::MAX - 1).nth(2), None);
+}
+
+#[test]
+fn split_at() {
+    let (head, rest) = Sequence::new(1_i32).split_at(10);
+    assert_eq!(head.collect::<Vec<_>>(), expected(1, |n| n + 1, 10));
+    let (empty, same) = rest.split_at(0);
+    assert_eq!(empty.count(), 0);
+    assert_eq!(same.take(5).collect::<Vec<_>>(), expected(11, |n| n + 1, 5));
+}
+
+#[test]
+fn chunks() {
+    let chunks: Vec<Vec<_>> = Sequence::with_step(5_i64, 3)
+        .chunks(7)
+        .take(4)
+        .map(|chunk| chunk.collect())
+        .collect();
+    assert_eq!(chunks.concat(), expected(5, |n| n + 3, 28));
+    assert!(chunks.iter().all(|chunk| chunk.len() == 7));
+
+    let mut chunks = Sequence::new(1_u64).chunks(1_000);
+    let chunk = chunks.nth(999_999).unwrap();
+    assert_eq!(
+        chunk.clone().next(),
+        Some((999_999_001, 999_999_001.fizzbuzz()))
+    );
+    assert_eq!(chunk.count(), 1_000);
+}
+
+#[test]
+fn last_chunk_is_short() {
+    let chunks: Vec<usize> = Sequence::new(240_u8)
+        .chunks(7)
+        .map(Iterator::count)
+        .collect();
+    assert_eq!(chunks, vec![7, 7, 2]);
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn chunks_in_parallel() {
+    use rayon::prelude::*;
+
+    let chunks: Vec<_> = Sequence::new(1_u64).chunks(10_000).take(16).collect();
+    let answers: Vec<(u64, FizzBuzzAnswer<u64>)> = chunks.into_par_iter().flatten_iter().collect();
+    assert_eq!(answers, expected(1, |n| n + 1, 160_000));
+}
+
+#[test]
+fn wide_steps_which_overflow_before_the_start_is_added() {
+    let mut sequence = Sequence::with_step(i128::MIN, 1 << 100);
+    assert_eq!(sequence.nth(1 << 27), Some((0, FizzBuzzAnswer::Fizzbuzz)));
+    let mut sequence = Sequence::with_step(u128::MAX, u128::MAX);
+    assert_eq!(sequence.next(), Some((u128::MAX, u128::MAX.fizzbuzz())));
+    assert_eq!(sequence.next(), None);
+}
+
+#[test]
+fn skips_long_chunks() {
+    let mut chunks = Sequence::new(0_u128).chunks(usize::MAX);
+    let chunk = chunks.nth(usize::MAX).unwrap();
+    let n = usize::MAX as u128 * usize::MAX as u128;
+    assert_eq!(chunk.take(1).next(), Some((n, n.fizzbuzz())));
+}
+
+#[test]
+fn size_hint() {
+    assert_eq!(
+        Sequence::new(1_u64).size_hint(),
+        (usize::MAX, Some(usize::MAX))
+    );
+    assert_eq!(Sequence::new(0_u128).size_hint(), (usize::MAX, None));
+    assert_eq!(
+        Sequence::with_step(5_i32, 0).size_hint(),
+        (usize::MAX, None)
+    );
+    assert_eq!(Sequence::new(250_u8).size_hint(), (6, Some(6)));
+    assert_eq!(Sequence::with_step(-120_i8, -3).size_hint(), (3, Some(3)));
+    assert_eq!(
+        Sequence::new(0_i128).chunks(1_000).size_hint(),
+        (usize::MAX, None)
+    );
+    assert_eq!(Sequence::new(240_u8).chunks(7).size_hint(), (3, Some(3)));
+    let mut sequence = Sequence::new(250_u8);
+    sequence.nth(5);
+    assert_eq!(sequence.size_hint(), (0, Some(0)));
+}
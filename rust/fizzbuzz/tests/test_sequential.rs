@@ -0,0 +1,117 @@
+#![cfg(feature = "alloc")]
+
+use std::cell::Cell;
+
+use fizzbuzz::{
+    game::Game, stateful::EveryNth, FizzBuzz, FizzBuzzAnswer, RuleSet, SequentialFizzBuzz,
+};
+
+#[test]
+fn matches_single_answers() {
+    let answers: Vec<String> = (-50..50_i32).fizzbuzz_seq().collect();
+    for (answer, i) in answers.iter().zip(-50..50_i32) {
+        let expected: String = i.fizzbuzz().into();
+        assert_eq!(answer, &expected, "for {i}");
+    }
+}
+
+#[test]
+fn vector() {
+    let answers: Vec<FizzBuzzAnswer<i32>> = vec![1, 3, 5, 15].into_iter().fizzbuzz_seq().collect();
+    assert_eq!(
+        answers,
+        vec![
+            FizzBuzzAnswer::Number(1),
+            FizzBuzzAnswer::Fizz,
+            FizzBuzzAnswer::Buzz,
+            FizzBuzzAnswer::Fizzbuzz
+        ]
+    );
+}
+
+#[test]
+fn is_lazy() {
+    let evaluated = Cell::new(0);
+    let mut answers = (1_u64..)
+        .inspect(|_| evaluated.set(evaluated.get() + 1))
+        .fizzbuzz_seq::<String>();
+    assert_eq!(evaluated.get(), 0);
+    assert_eq!(answers.nth(14).unwrap(), "fizzbuzz");
+    assert_eq!(evaluated.get(), 15);
+}
+
+#[test]
+fn custom_rules() {
+    let rules = RuleSet::default().divisor(7, "woof");
+    let answers: Vec<String> = (20..=22).fizzbuzz_seq_with(&rules).collect();
+    assert_eq!(answers, vec!["buzz", "fizzwoof", "22"]);
+}
+
+#[test]
+fn static_rules() {
+    let answers: Vec<String> = (6..=10).fizzbuzz_seq_as::<Game<3, 7>, _>().collect();
+    assert_eq!(answers, vec!["fizz", "buzz", "8", "fizz", "10"]);
+}
+
+#[test]
+fn scan() {
+    let bang = EveryNth::new("fizz", 3, "bang");
+    let answers: Vec<String> = (1..13).fizzbuzz_seq_scan(&bang).collect();
+    assert_eq!(answers[2], "fizz");
+    assert_eq!(answers[8], "bang");
+    assert_eq!(answers[11], "fizz");
+}
+
+#[test]
+fn scan_with_rules() {
+    let rules = RuleSet::default().divisor(2, "even");
+    let second = EveryNth::new("even", 2, "odd?");
+    let answers: Vec<String> = (1..9).fizzbuzz_seq_with_scan(&rules, &second).collect();
+    assert_eq!(
+        answers,
+        vec!["1", "even", "fizz", "odd?", "buzz", "fizzeven", "7", "even"]
+    );
+}
+
+#[test]
+fn write_fmt() {
+    let mut out = String::new();
+    (1..6).write_fizzbuzz_seq_fmt(&mut out, ", ").unwrap();
+    assert_eq!(out, "1, 2, fizz, 4, buzz");
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn write_io() {
+    let mut out = Vec::new();
+    (1..16_u8).write_fizzbuzz_seq(&mut out, "\n").unwrap();
+    let expected: Vec<String> = (1..16_u8).map(|i| i.fizzbuzz().into()).collect();
+    assert_eq!(String::from_utf8(out).unwrap(), expected.join("\n"));
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn write_io_in_blocks() {
+    let mut out = Vec::new();
+    (1..100_000_u32).write_fizzbuzz_seq(&mut out, "\n").unwrap();
+    let expected: Vec<String> = (1..100_000_u32).map(|i| i.fizzbuzz().into()).collect();
+    assert_eq!(String::from_utf8(out).unwrap(), expected.join("\n"));
+}
+
+#[test]
+fn write_empty() {
+    let mut out = String::new();
+    (1..1).write_fizzbuzz_seq_fmt(&mut out, ", ").unwrap();
+    assert_eq!(out, "");
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn imported_with_multi_fizzbuzz() {
+    use fizzbuzz::MultiFizzBuzz;
+    use rayon::iter::ParallelIterator;
+
+    let sequential: Vec<String> = (1..100).fizzbuzz_seq().collect();
+    let parallel: Vec<String> = (1..100).fizzbuzz().collect();
+    assert_eq!(sequential, parallel);
+}
@@ -0,0 +1,193 @@
+#![cfg(feature = "rayon")]
+
+use fizzbuzz::{
+    parallel,
+    stateful::{EveryNth, Monoid},
+    FizzBuzz, FizzBuzzAnswer, MultiFizzBuzz, RuleSet, StatefulRule,
+};
+use rayon::iter::ParallelIterator;
+
+/// Numbers are said with their digits reversed after an odd number of "buzz"es
+struct ReverseAfterBuzz;
+
+#[derive(Clone)]
+struct Reversed(bool);
+
+impl Monoid for Reversed {
+    fn empty() -> Self {
+        Reversed(false)
+    }
+
+    fn combine(&self, next: &Self) -> Self {
+        Reversed(self.0 ^ next.0)
+    }
+}
+
+impl StatefulRule<i32> for ReverseAfterBuzz {
+    type State = Reversed;
+
+    fn observe(&self, _num: &i32, answer: &FizzBuzzAnswer<i32>) -> Reversed {
+        Reversed(answer == &FizzBuzzAnswer::Buzz)
+    }
+
+    fn answer(
+        &self,
+        before: &Reversed,
+        _num: &i32,
+        answer: FizzBuzzAnswer<i32>,
+    ) -> FizzBuzzAnswer<i32> {
+        match answer {
+            FizzBuzzAnswer::Number(n) if before.0 => {
+                let reversed: String = n.to_string().chars().rev().collect();
+                FizzBuzzAnswer::Word(reversed.into())
+            }
+            answer => answer,
+        }
+    }
+}
+
+/// Says "again" for a number which follows another number
+struct Again;
+
+/// The number of consecutive numbers said. Not associative
+#[derive(Clone)]
+struct Streak(usize);
+
+impl Monoid for Streak {
+    const ASSOCIATIVE: bool = false;
+
+    fn empty() -> Self {
+        Streak(0)
+    }
+
+    fn combine(&self, next: &Self) -> Self {
+        match next.0 {
+            0 => Streak(0),
+            n => Streak(self.0 + n),
+        }
+    }
+}
+
+impl StatefulRule<i32> for Again {
+    type State = Streak;
+
+    fn observe(&self, _num: &i32, answer: &FizzBuzzAnswer<i32>) -> Streak {
+        match answer {
+            FizzBuzzAnswer::Number(_) => Streak(1),
+            _ => Streak(0),
+        }
+    }
+
+    fn answer(
+        &self,
+        before: &Streak,
+        _num: &i32,
+        answer: FizzBuzzAnswer<i32>,
+    ) -> FizzBuzzAnswer<i32> {
+        match answer {
+            FizzBuzzAnswer::Number(_) if before.0 > 0 => FizzBuzzAnswer::Word("again".into()),
+            answer => answer,
+        }
+    }
+}
+
+/// Applies `rule` sequentially, for comparison
+fn sequential<Rule: StatefulRule<i32>>(
+    nums: impl Iterator<Item = i32>,
+    rule: &Rule,
+) -> Vec<FizzBuzzAnswer<i32>> {
+    let mut state = Rule::State::empty();
+    nums.map(|n| {
+        let answer = n.fizzbuzz();
+        let observed = rule.observe(&n, &answer);
+        let answer = rule.answer(&state, &n, answer);
+        state = state.combine(&observed);
+        answer
+    })
+    .collect()
+}
+
+#[test]
+fn every_third_fizz_is_bang() {
+    let bang = EveryNth::new("fizz", 3, "bang");
+    let answers: Vec<String> = (1..28).fizzbuzz_scan(&bang).collect();
+    let fizzes: Vec<&str> = answers
+        .iter()
+        .map(String::as_str)
+        .filter(|a| ["fizz", "bang"].contains(a))
+        .collect();
+    assert_eq!(
+        fizzes,
+        vec!["fizz", "fizz", "bang", "fizz", "fizz", "bang", "fizz", "fizz"]
+    );
+    assert_eq!(answers[14], "fizzbuzz");
+}
+
+#[test]
+fn classic_replacement_is_classic_answer() {
+    let fizz = EveryNth::new("buzz", 2, "fizz");
+    let answers: Vec<FizzBuzzAnswer<i32>> = (1..11).fizzbuzz_scan(&fizz).collect();
+    assert_eq!(answers[9], FizzBuzzAnswer::Fizz);
+    let rules = RuleSet::new().divisor(10, "fizz");
+    assert_eq!(answers[9], 10.fizzbuzz_with(&rules));
+}
+
+#[test]
+fn with_rules() {
+    let rules = RuleSet::new().divisor(2, "even");
+    let every_other = EveryNth::new("even", 2, "odd?");
+    let answers: Vec<String> = vec![1, 2, 3, 4, 5, 6, 8]
+        .fizzbuzz_with_scan(&rules, &every_other)
+        .collect();
+    assert_eq!(answers, vec!["1", "even", "3", "odd?", "5", "even", "odd?"]);
+}
+
+#[test]
+fn reverse_after_buzz() {
+    let answers: Vec<String> = (8..22).fizzbuzz_scan(&ReverseAfterBuzz).collect();
+    assert_eq!(
+        answers,
+        vec![
+            "8", "fizz", "buzz", "11", "fizz", "31", "41", "fizzbuzz", "61", "71", "fizz", "91",
+            "buzz", "fizz"
+        ]
+    );
+}
+
+#[test]
+fn big_range_is_well_ordered() {
+    let answers: Vec<FizzBuzzAnswer<_>> = (1..1_000_000).fizzbuzz_scan(&ReverseAfterBuzz).collect();
+    assert_eq!(answers, sequential(1..1_000_000, &ReverseAfterBuzz));
+}
+
+#[test]
+fn empty_always_parallel() {
+    let answers: Vec<FizzBuzzAnswer<i32>> =
+        parallel::with_threshold(0, || (0..0).fizzbuzz_scan(&ReverseAfterBuzz).collect());
+    assert!(answers.is_empty());
+}
+
+#[test]
+fn non_associative_is_sequential() {
+    let answers: Vec<FizzBuzzAnswer<_>> = (1..1_000_000).fizzbuzz_scan(&Again).collect();
+    assert_eq!(
+        answers[..8].iter().map(|a| a.word()).collect::<Vec<_>>(),
+        vec![
+            None,
+            Some("again"),
+            Some("fizz"),
+            None,
+            Some("buzz"),
+            Some("fizz"),
+            None,
+            Some("again")
+        ]
+    );
+    assert_eq!(answers, sequential(1..1_000_000, &Again));
+}
+
+#[test]
+#[should_panic]
+fn every_zeroth() {
+    EveryNth::new("fizz", 0, "bang");
+}
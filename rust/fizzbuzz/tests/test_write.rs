@@ -0,0 +1,88 @@
+#![cfg(feature = "rayon")]
+
+use std::io;
+
+use fizzbuzz::{FizzBuzz, MultiFizzBuzz};
+use rayon::iter::ParallelIterator;
+
+mod single {
+    use super::*;
+
+    #[test]
+    fn fmt_sink() {
+        let mut out = String::new();
+        for n in [1, 3, 5, 15, -7] {
+            n.write_fizzbuzz_fmt(&mut out).unwrap();
+        }
+        assert_eq!(out, "1fizzbuzzfizzbuzz-7");
+    }
+
+    #[test]
+    fn io_sink() {
+        let mut out = Vec::new();
+        for n in [14.5_f64, 15.0] {
+            n.write_fizzbuzz(&mut out).unwrap();
+        }
+        assert_eq!(out, b"14.5fizzbuzz");
+    }
+}
+
+mod multi {
+    use super::*;
+
+    #[test]
+    fn fmt_sink() {
+        let mut out = String::new();
+        (1..16).write_fizzbuzz_fmt(&mut out, ", ").unwrap();
+        assert_eq!(
+            out,
+            "1, 2, fizz, 4, buzz, fizz, 7, 8, fizz, buzz, 11, fizz, 13, 14, fizzbuzz"
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let mut out = Vec::new();
+        Vec::<u8>::new().write_fizzbuzz(&mut out, "\n").unwrap();
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn empty_separator() {
+        let mut out = String::new();
+        vec![3, 4, 5].write_fizzbuzz_fmt(&mut out, "").unwrap();
+        assert_eq!(out, "fizz4buzz");
+    }
+
+    #[test]
+    fn big_range_is_well_ordered() {
+        let mut out = Vec::new();
+        (1..3_000_000_u32).write_fizzbuzz(&mut out, "\n").unwrap();
+        let expected: Vec<String> = (1..3_000_000_u32).fizzbuzz().collect();
+        assert_eq!(String::from_utf8(out).unwrap(), expected.join("\n"));
+    }
+
+    /// Accepts a limited number of bytes
+    struct Full(usize);
+
+    impl io::Write for Full {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.0 == 0 {
+                return Err(io::Error::new(io::ErrorKind::StorageFull, "full"));
+            }
+            let written = buf.len().min(self.0);
+            self.0 -= written;
+            Ok(written)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn io_errors_are_returned() {
+        let result = (1..1_000_000).write_fizzbuzz(&mut Full(1_000), "\n");
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::StorageFull);
+    }
+}
@@ -1,8 +1,8 @@
-use std::{borrow::Cow, ops::Neg};
+use std::{borrow::Cow, fmt::Display};
 
-use fizzbuzz::{FizzBuzz, FizzBuzzAnswer, MultiFizzBuzz};
+use fizzbuzz::{range::SliceSpec, FizzBuzz, FizzBuzzAnswer, MultiFizzBuzz};
 use pyo3::{exceptions::PyValueError, prelude::*, types::PySlice};
-use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+use rayon::iter::ParallelIterator;
 
 #[derive(FromPyObject)]
 enum FizzBuzzable {
@@ -40,8 +40,8 @@ enum FizzBuzzReturn {
     Many(Vec<Cow<'static, str>>),
 }
 
-impl From<FizzBuzzAnswer> for FizzBuzzReturn {
-    fn from(value: FizzBuzzAnswer) -> Self {
+impl<Num: Display> From<FizzBuzzAnswer<Num>> for FizzBuzzReturn {
+    fn from(value: FizzBuzzAnswer<Num>) -> Self {
         FizzBuzzReturn::One(value.into())
     }
 }
@@ -96,45 +96,17 @@ fn py_fizzbuzz(num: FizzBuzzable) -> PyResult<FizzBuzzReturn> {
         FizzBuzzable::Int(n) => Ok(n.fizzbuzz().into()),
         FizzBuzzable::Float(n) => Ok(n.fizzbuzz().into()),
         FizzBuzzable::Vec(v) => Ok(FizzBuzzReturn::Many(v.fizzbuzz().collect())),
-        FizzBuzzable::Slice(s) => match s.step {
-            // Can only be tested from python: Cannot create a PySlice with no step in rust.
-            None => Ok(FizzBuzzReturn::Many((s.start..s.stop).fizzbuzz().collect())), // GRCOV_EXCL_LINE
-
-            Some(1) => Ok(FizzBuzzReturn::Many((s.start..s.stop).fizzbuzz().collect())),
-
-            Some(step) => match step {
-                1.. => Ok(FizzBuzzReturn::Many(
-                    (s.start..s.stop)
-                        .into_par_iter()
-                        .step_by(step.try_into().unwrap())
-                        .fizzbuzz()
-                        .collect(),
-                )),
-
-                //  ```python
-                //  >>> foo[1:5:0]
-                //  Traceback (most recent call last):
-                //    File "<stdin>", line 1, in <module>
-                //  ValueError: slice step cannot be zero
-                //  ```
-                0 => Err(PyValueError::new_err("step cannot be zero")),
-
-                //  ```python
-                //  >>> foo=[0,1,2,3,4,5,6]
-                //  >>> foo[6:0:-2]
-                //  [6, 4, 2]
-                //  ```
-                // Rust doesn't accept step < 0 or stop < start so need some trickery
-                ..=-1 => Ok(FizzBuzzReturn::Many(
-                    (s.start.neg()..s.stop.neg())
-                        .into_par_iter()
-                        .step_by(step.neg().try_into().unwrap())
-                        .map(|x| x.neg())
-                        .fizzbuzz()
-                        .collect(),
-                )),
-            },
-        },
+        FizzBuzzable::Slice(s) => {
+            //  ```python
+            //  >>> foo[1:5:0]
+            //  Traceback (most recent call last):
+            //    File "<stdin>", line 1, in <module>
+            //  ValueError: slice step cannot be zero
+            //  ```
+            let slice = SliceSpec::new(Some(s.start), Some(s.stop), s.step)
+                .map_err(|err| PyValueError::new_err(err.to_string()))?;
+            Ok(FizzBuzzReturn::Many(slice.fizzbuzz().collect()))
+        }
     }
 }
 
@@ -299,6 +271,19 @@ mod tests {
         let result: Vec<String> = fizzbuzz!(input);
         assert_eq!(result, expected);
     }
+    #[pyo3test]
+    #[pyo3import(py_fizzbuzzo3: from fizzbuzzo3 import fizzbuzz)]
+    fn test_fizzbuzz_slice_negative_step_min() {
+        let input = MySlice {
+            start: isize::MIN + 2,
+            stop: isize::MIN,
+            step: Some(-1),
+        };
+        let expected = vec!["fizz".to_string(), (isize::MIN + 1).to_string()];
+        let result: Vec<String> = fizzbuzz!(input);
+        assert_eq!(result, expected);
+    }
+
     #[pyo3test]
     #[allow(unused_macros)]
     #[pyo3import(py_fizzbuzzo3: from fizzbuzzo3 import fizzbuzz)]